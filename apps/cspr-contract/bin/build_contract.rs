@@ -1,5 +1,10 @@
 #![doc = "Binary for building wasm files from odra contracts."]
-#![no_std]
-#![no_main]
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
 #![allow(unused_imports, clippy::single_component_path_imports)]
 use cspr_contract;
+
+/// Only meaningful as a wasm32 build through `cargo odra build`; the host
+/// build exists so `cargo test` can build every target.
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {}
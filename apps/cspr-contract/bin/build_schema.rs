@@ -1,16 +1,24 @@
 #![doc = "Binary for building schema definitions from odra contracts."]
-#[allow(unused_imports)]
+#[allow(unused_imports, clippy::single_component_path_imports)]
 use cspr_contract;
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(odra_module = "")))]
 extern "Rust" {
     fn module_schema() -> odra::contract_def::ContractBlueprint;
     fn casper_contract_schema() -> odra::schema::casper_contract_schema::ContractSchema;
 }
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(odra_module = "")))]
 fn main() {
     odra_build::schema(unsafe { crate::module_schema() }, unsafe {
         crate::casper_contract_schema()
     });
 }
+
+/// The schema symbols only exist when `ODRA_MODULE` names a contract, as
+/// `cargo odra schema` does; a plain build (e.g. for `cargo test`) gets a stub.
+#[cfg(all(not(target_arch = "wasm32"), odra_module = ""))]
+fn main() {
+    eprintln!("Run `cargo odra schema`, which sets ODRA_MODULE");
+    std::process::exit(1);
+}
@@ -182,7 +182,7 @@ impl DeployScript for AnchoreDeployScript {
         println!("💡 NEXT STEPS:");
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
         println!("\n1. ✓ Save these addresses for your configuration");
-        println!("2. ✓ Users can now mint tokens (public mint function)");
+        println!("2. ✓ Grant MINTER (grant_role) to faucet accounts that mint tokens");
         println!("3. ✓ Users can provide liquidity to pools");
         println!("4. ✓ Users can swap between tokens");
         println!("5. ✓ Update your frontend with these addresses\n");
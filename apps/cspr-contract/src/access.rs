@@ -0,0 +1,108 @@
+//! Shared admin and role management for the Anchore contracts.
+//!
+//! A single admin, handed over in two steps (`propose_admin`, then
+//! `accept_admin` by the proposed account), grants and revokes the other
+//! roles. Contracts embed `AccessControl` as a submodule, expose its
+//! entrypoints with `delegate!`, and gate their own entrypoints with
+//! `has_role`, reverting with their own errors.
+
+use odra::prelude::*;
+use crate::events::{AdminTransferProposed, AdminTransferred, RoleGranted, RoleRevoked};
+
+// Error definitions, AccessControl codes start at 30_000
+#[odra::odra_error]
+pub enum Error {
+    NotInitialized = 30_000,
+    NotAdmin = 30_001,
+    NotPendingAdmin = 30_002,
+    AdminRoleNotGrantable = 30_003,
+}
+
+#[odra::odra_type]
+pub enum Role {
+    /// Held by exactly one account, transferred with propose/accept_admin
+    Admin,
+    /// Freezes pending bridge releases
+    Guardian,
+    /// Registers operators in the OperatorRegistry
+    OperatorManager,
+    /// Sets bridge and pool fees
+    FeeManager,
+    /// Mints and burns tokens
+    Minter,
+}
+
+#[odra::module]
+pub struct AccessControl {
+    admin: Var<Address>,
+    pending_admin: Var<Option<Address>>,
+    roles: Mapping<(Role, Address), bool>,
+}
+
+#[odra::module]
+impl AccessControl {
+    pub fn init(&mut self, admin: Address) {
+        self.admin.set(admin);
+    }
+
+    /// First step of an admin handover; replaces any earlier proposal
+    pub fn propose_admin(&mut self, new_admin: Address) {
+        let admin = self.assert_admin();
+        self.pending_admin.set(Some(new_admin));
+        self.env().emit_event(AdminTransferProposed { admin, proposed: new_admin });
+    }
+
+    /// Completes a handover, called by the proposed admin
+    pub fn accept_admin(&mut self) {
+        let caller = self.env().caller();
+        if self.pending_admin.get_or_default() != Some(caller) {
+            self.env().revert(Error::NotPendingAdmin);
+        }
+        let previous = self.get_admin();
+        self.admin.set(caller);
+        self.pending_admin.set(None);
+        self.env().emit_event(AdminTransferred { previous, admin: caller });
+    }
+
+    pub fn grant_role(&mut self, role: Role, account: Address) {
+        let admin = self.assert_admin();
+        if role == Role::Admin {
+            self.env().revert(Error::AdminRoleNotGrantable);
+        }
+        self.roles.set(&(role.clone(), account), true);
+        self.env().emit_event(RoleGranted { role, account, sender: admin });
+    }
+
+    pub fn revoke_role(&mut self, role: Role, account: Address) {
+        let admin = self.assert_admin();
+        if role == Role::Admin {
+            self.env().revert(Error::AdminRoleNotGrantable);
+        }
+        self.roles.set(&(role.clone(), account), false);
+        self.env().emit_event(RoleRevoked { role, account, sender: admin });
+    }
+
+    pub fn has_role(&self, role: Role, account: Address) -> bool {
+        match role {
+            Role::Admin => self.admin.get() == Some(account),
+            role => self.roles.get_or_default(&(role, account)),
+        }
+    }
+
+    pub fn get_admin(&self) -> Address {
+        self.admin.get_or_revert_with(Error::NotInitialized)
+    }
+
+    pub fn get_pending_admin(&self) -> Option<Address> {
+        self.pending_admin.get_or_default()
+    }
+
+    /// Reverts unless the caller is the admin, returns the admin
+    fn assert_admin(&self) -> Address {
+        let admin = self.get_admin();
+        if self.env().caller() != admin {
+            self.env().revert(Error::NotAdmin);
+        }
+        admin
+    }
+}
@@ -1,98 +1,1882 @@
 use odra::prelude::*;
-use odra::casper_types::U256;
-use odra::ContractRef;
-use crate::events::{BridgeRelease, OperatorUpdated};
+use odra::casper_types::bytesrepr::{Bytes, FromBytes, ToBytes};
+use odra::casper_types::{PublicKey, RuntimeArgs, U256};
+use odra::{CallDef, ContractRef};
+use crate::access::{AccessControl, Role};
+use crate::crypto::{self, RecipientError};
+use crate::liquidity::{BridgeLiquidity, Error as LiquidityError};
+use crate::events::{
+    BondConfigUpdated, BondWithdrawn, Bonded, BridgeRelease, BridgeRequested,
+    ChallengePeriodUpdated, ChallengeResolved, EpochActivated, EpochGracePeriodUpdated,
+    FeeConfigUpdated, FeesClaimed, LimitsUpdated,
+    MessageAttested, MessageExecuted, MessageReceived, MessageTargetUpdated, OperatorSetProposed,
+    OperatorSlashed, PoolUpdated, RateLimitHit, ReleaseBatchAttested, ReleaseBatchPosted,
+    ReleaseClaimed, ReleaseQueued, RouteRemoved, RouteUpdated, SwapFallback, TokenModeUpdated, TransferAttested, TransferChallenged, TreasuryUpdated,
+    UnbondingStarted,
+};
+use crate::operator_registry::OperatorRegistryInterfaceContractRef;
+use crate::pool::AnchorePoolContractRef;
 
-// Link to the AMM Module
-#[odra::external_contract]
-pub trait AnchoreAMM {
-    fn swap_exact_tokens(&mut self, amount_in: U256, token_in: Address, to: Address);
+// Error definitions
+#[odra::odra_error]
+pub enum Error {
+    NotInitialized = 1,
+    Unauthorized = 2,
+    NotOperator = 3,
+    NonceAlreadyProcessed = 4,
+    ZeroAmount = 5,
+    InvalidThreshold = 6,
+    AlreadyAttested = 7,
+    AttestationRequired = 8,
+    InvalidPayload = 9,
+    InvalidSignature = 10,
+    DuplicateSigner = 11,
+    ThresholdNotMet = 12,
+    InvalidRecipient = 13,
+    UnsupportedToken = 14,
+    UnknownRoute = 15,
+    InvalidDecimals = 16,
+    TransferAboveMaximum = 17,
+    RateLimitExceeded = 18,
+    NotPending = 19,
+    ReleaseLocked = 20,
+    AlreadyPending = 21,
+    InvalidLimits = 22,
+    FeeExceedsAmount = 23,
+    InvalidFeeConfig = 24,
+    NothingToClaim = 25,
+    BondNotConfigured = 26,
+    InvalidBondConfig = 27,
+    InsufficientBond = 28,
+    UnbondingInProgress = 29,
+    NoConflict = 30,
+    ReleaseChallenged = 31,
+    NotChallenger = 32,
+    ChallengeWindowClosed = 33,
+    NotChallenged = 34,
+    InvalidOperatorSet = 35,
+    NoProposedOperatorSet = 36,
+    EpochNotAccepted = 37,
+    TargetNotAllowed = 38,
+    UnknownMessage = 39,
+    MessageAlreadyExecuted = 40,
+    EmptyBatch = 41,
+    UnknownBatch = 42,
+    InvalidProof = 43,
+    AlreadyClaimed = 44,
+    UnknownRecipientTag = 45,
+    MalformedRecipient = 46,
+    InexactAmount = 47,
+    BatchExpired = 48,
+}
+
+/// How the bridge custodies a Casper token
+#[odra::odra_type]
+pub enum TokenMode {
+    /// Native Casper asset: escrowed on bridge out, released from escrow
+    LockUnlock,
+    /// Wrapped EVM asset (`BridgedToken`): burned on bridge out, minted on release
+    MintBurn,
+}
+
+/// A release as attested by operators. Operators attest to the hash of
+/// the whole payload, so attestations only add up for identical payloads,
+/// made in the same operator set epoch.
+/// The Casper token is resolved on-chain from (source_chain_id, evm_token),
+/// and replay protection is keyed on (source_chain_id, source_contract, nonce).
+/// The recipient is given as tagged bytes, see `crypto::recipient_address`.
+#[odra::odra_type]
+pub struct ReleasePayload {
+    pub transfer_id: [u8; 32],
+    pub source_tx_hash: [u8; 32], // EVM transaction that emitted the transfer
+    pub source_chain_id: U256,
+    pub source_contract: [u8; 20], // The AnchoreVault that emitted the transfer
+    pub evm_token: [u8; 20],
+    pub recipient: Bytes, // Public key or account hash, as sent to AnchoreVault.bridgeOut
+    pub amount: U256, // In the EVM token's decimals
+    pub nonce: U256,
+    pub target_token: Option<Address>, // Swap into this token through its AnchorePool
+    pub min_amount_out: U256,          // Of target_token, else the bridged token is delivered
+    pub epoch: u32, // Operator set epoch the payload is attested or signed in
+}
+
+/// Operators releasing transfers during an epoch, and how many must agree
+#[odra::odra_type]
+pub struct OperatorSet {
+    pub operators: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// Casper token an EVM token is released as, with the decimals needed to
+/// convert amounts between the two
+#[odra::odra_type]
+pub struct TokenRoute {
+    pub token: Address,
+    pub source_decimals: u8,
+    pub local_decimals: u8,
+}
+
+/// Per-token release limits, in the token's local decimals. A zero field
+/// disables that limit. Durations are in milliseconds of block time.
+#[odra::odra_type]
+#[derive(Default)]
+pub struct TokenLimits {
+    pub window_cap: U256,               // Max released per rolling window
+    pub window_duration: u64,           // Time for a full window_cap of usage to decay
+    pub max_per_transfer: U256,         // Larger releases revert
+    pub large_transfer_threshold: U256, // Releases from here on are pending
+    pub delay: u64,                     // Hold time of releases over the window cap
+}
+
+/// Release fee for a route, in the local token: `fee_bps` of the amount but
+/// at least `min_fee`. `treasury_share_bps` of the fee goes to the treasury
+/// and `lp_share_bps` to the token's liquidity providers, or to the treasury
+/// while there are none. The rest is split between the operators that
+/// attested or signed.
+#[odra::odra_type]
+#[derive(Default)]
+pub struct FeeConfig {
+    pub fee_bps: u32,
+    pub min_fee: U256,
+    pub treasury_share_bps: u32,
+    pub lp_share_bps: u32,
+}
+
+/// Operator bonding terms. Operators need `min_bond` of `token` bonded to be
+/// active, and wait `unbonding_delay` milliseconds before withdrawing.
+#[odra::odra_type]
+pub struct BondConfig {
+    pub token: Address,
+    pub min_bond: U256,
+    pub unbonding_delay: u64,
+}
+
+/// Bond an operator has asked to withdraw, still slashable until withdrawn
+#[odra::odra_type]
+pub struct Unbonding {
+    pub amount: U256,
+    pub unlock_at: u64,
+}
+
+/// Release amount counted against the rolling window cap, as of `updated_at`
+#[odra::odra_type]
+#[derive(Default)]
+pub struct WindowUsage {
+    pub updated_at: u64,
+    pub used: U256,
+}
+
+/// Lifecycle of a release held back by a limit
+#[odra::odra_type]
+pub enum ReleaseStatus {
+    /// Challengeable until `unlock_at`, then finalizable
+    Pending,
+    /// Frozen until the admin resolves the challenge
+    Challenged,
+}
+
+/// Release held back by a limit, finalizable with `finalize` from `unlock_at`.
+/// Fees are only credited to `operators` and the treasury on finalization.
+#[odra::odra_type]
+pub struct PendingRelease {
+    pub transfer_id: [u8; 32],
+    pub source_tx_hash: [u8; 32],
+    pub token: Address,
+    pub recipient: Address,
+    pub amount: U256, // After fees
+    pub fee: U256,
+    pub treasury_fee: U256,
+    pub lp_fee: U256,
+    pub operators: Vec<Address>,
+    pub nonce: U256,
+    pub target_token: Option<Address>,
+    pub min_amount_out: U256,
+    pub unlock_at: u64,
+    pub status: ReleaseStatus,
+}
+
+/// A contract call requested from an EVM chain. `args` are bytesrepr-encoded
+/// `RuntimeArgs` for `entry_point` on `target`.
+#[odra::odra_type]
+pub struct CrossChainMessage {
+    pub source_chain_id: U256,
+    pub sender: [u8; 20], // The EVM account that sent the message
+    pub nonce: U256,
+    pub target: Address,
+    pub entry_point: String,
+    pub args: Bytes,
+}
+
+/// Outcome of the last execution attempt of a delivered message
+#[odra::odra_type]
+pub enum MessageStatus {
+    /// Not executed yet
+    Delivered,
+    Executed,
+    /// The target returned false; can be retried
+    Failed,
 }
 
+#[odra::odra_type]
+pub struct DeliveredMessage {
+    pub message: CrossChainMessage,
+    pub status: MessageStatus,
+    pub attempts: u32,
+}
+
+/// Merkle root over a batch of releases, posted once `threshold` operators
+/// of `epoch` attested it. Each leaf commits to a `ReleasePayload` and its
+/// index in the batch; see `crypto::release_leaf`.
+#[odra::odra_type]
+pub struct ReleaseBatch {
+    pub root: [u8; 32],
+    pub leaf_count: u64,
+    pub epoch: u32,
+    pub operators: Vec<Address>, // Attesters, credited with the claims' fees
+}
+
+/// Cumulative flows of a Casper token through the bridge, in its local
+/// decimals, for reconciling against the EVM vaults. `released` is what
+/// recipients got and `fees` what was withheld from them, so inbound
+/// releases total `released + fees`; releases still pending count once
+/// finalized. `bridged_out` is what was escrowed or burned for EVM.
+#[odra::odra_type]
+#[derive(Default)]
+pub struct RouteAccounting {
+    pub released: U256,
+    pub fees: U256,
+    pub bridged_out: U256,
+}
+
+/// Upper bound on token decimals, keeps 10^decimals within U256
+const MAX_DECIMALS: u8 = 32;
+
+/// Denominator of basis point fees and shares
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// How long payloads of the previous epoch are accepted after a rotation,
+/// in milliseconds, until changed with `set_epoch_grace_period`
+const DEFAULT_EPOCH_GRACE_PERIOD: u64 = 60 * 60 * 1000;
+
+/// How long large releases stay open to challenges, in milliseconds, until
+/// changed with `set_challenge_period`
+const DEFAULT_CHALLENGE_PERIOD: u64 = 24 * 60 * 60 * 1000;
+
 // Link to CEP-18
 #[odra::external_contract]
 pub trait Cep18 {
     fn transfer(&mut self, recipient: Address, amount: U256);
+    fn transfer_from(&mut self, owner: Address, recipient: Address, amount: U256);
+    fn approve(&mut self, spender: Address, amount: U256);
+    fn balance_of(&self, address: Address) -> U256;
 }
 
+// Link to BridgedToken mint/burn, restricted to this bridge
+#[odra::external_contract]
+pub trait MintableToken {
+    fn mint(&mut self, recipient: Address, amount: U256);
+    fn burn(&mut self, owner: Address, amount: U256);
+}
+
+/// AnchoreBridge: Casper side of the Anchore EVM bridge
+/// Implements:
+/// - propose_admin / accept_admin / grant_role: Two-step admin handover and
+///   FEE_MANAGER and GUARDIAN roles
+/// - Operators are registered in the `OperatorRegistry` given at init,
+///   queried through `OperatorRegistryInterface`
+/// - propose_operator_set / handover_operator_set / approve_operator_set:
+///   Epochs of operator sets with their threshold, rotated on signatures of
+///   the current set or admin approval, with a grace period for the last one
+/// - attest: M-of-N operator attestation, releasing funds once `threshold`
+///   operators of the payload's epoch attested the same payload
+/// - receive_with_signatures: Permissionless release backed by `threshold`
+///   operator signatures, so any relayer can submit and pay the gas
+/// - receive_from_bridge: Single-operator release, only while threshold is 1
+/// - set_pool: AnchorePools releases swap through when the payload names a
+///   `target_token`, falling back to the bridged token on slippage
+/// - bridge_out: Escrows or burns tokens for release to an EVM recipient
+/// - set_token_mode: Registry deciding lock/unlock vs mint/burn per token
+/// - set_route / remove_route: Registry mapping EVM tokens to Casper tokens
+/// - set_limits / finalize: Per-token rate limits, with large or over-cap
+///   releases held pending for a challenge period
+/// - set_challenge_period / challenge / resolve_challenge: GUARDIANs and
+///   bonded watchers freeze pending releases for admin review
+/// - set_fee_config / claim_operator_fees: Per-route release fees shared
+///   between the releasing operators and the treasury
+/// - bond / start_unbonding / withdraw_bond / slash: CEP-18 operator bonds,
+///   slashable on proof of signing conflicting payloads
+/// - add_liquidity / remove_liquidity: LP shares of the deposits in
+///   lock/unlock tokens that releases draw from, earning a fee share
+/// - attest_batch / claim: Operator-attested Merkle roots over batches of
+///   releases, claimed one leaf at a time by recipients or relayers
+/// - get_route_accounting: Cumulative released, fee and outbound amounts
+///   per token, for reconciling against the EVM vaults
+/// - receive_message / execute_message: Operator-attested EVM messages,
+///   dispatched to allowlisted target entry points and retryable on failure
 #[odra::module]
 pub struct AnchoreBridge {
-    pub admin: Var<Address>,
-    pub amm_address: Var<Address>, // The AMM we route swaps to
-    pub operators: Mapping<Address, bool>,
-    pub processed_nonces: Mapping<U256, bool>,
+    pub access: SubModule<AccessControl>,
+    pub pools: Mapping<(Address, Address), Option<Address>>, // Sorted token pair -> AnchorePool
+    pub operator_registry: Var<Address>,
+    pub processed: Mapping<(U256, [u8; 20], U256), bool>, // (source_chain_id, source_contract, nonce)
+    pub epoch: Var<u32>,
+    pub operator_sets: Mapping<u32, Option<OperatorSet>>, // Per epoch
+    pub epoch_started_at: Var<u64>,
+    pub proposed_operator_set: Var<Option<OperatorSet>>, // For the next epoch
+    pub epoch_grace_period: Var<u64>,
+    pub attestations: Mapping<([u8; 32], Address), bool>, // (payload_hash, operator)
+    pub attesters: Mapping<[u8; 32], Vec<Address>>, // Operators per payload_hash, in order
+    pub outbound_nonce: Var<U256>,
+    pub token_modes: Mapping<Address, TokenMode>,
+    pub routes: Mapping<(U256, [u8; 20]), Option<TokenRoute>>, // (source_chain_id, evm_token)
+    pub outbound_routes: Mapping<(U256, Address), Option<[u8; 20]>>, // (chain_id, token) -> evm_token
+    pub limits: Mapping<Address, TokenLimits>,
+    pub window_usage: Mapping<Address, WindowUsage>,
+    pub pending: Mapping<[u8; 32], Option<PendingRelease>>, // transfer_id
+    pub pending_ids: Var<Vec<[u8; 32]>>,
+    pub challenge_period: Var<u64>, // Hold time of large releases
+    pub treasury: Var<Address>,
+    pub fee_configs: Mapping<(U256, [u8; 20]), FeeConfig>, // (source_chain_id, evm_token)
+    pub operator_fees: Mapping<(Address, Address), U256>,  // (operator, token)
+    pub treasury_fees: Mapping<Address, U256>,
+    pub reserved_fees: Mapping<Address, U256>, // Accrued and unclaimed, per token
+    pub pending_amounts: Mapping<Address, U256>, // Held by pending releases, per token
+    pub bond_config: Var<BondConfig>,
+    pub bonds: Mapping<Address, U256>,
+    pub unbondings: Mapping<Address, Option<Unbonding>>,
+    pub total_bonded: Var<U256>, // Bonded and unbonding
+    pub liquidity: SubModule<BridgeLiquidity>,
+    pub message_targets: Mapping<(Address, String), bool>, // (target, entry_point)
+    pub message_attestations: Mapping<([u8; 32], Address), bool>, // (message_hash, operator)
+    pub message_attesters: Mapping<[u8; 32], Vec<Address>>,
+    pub messages: Mapping<(U256, [u8; 20], U256), Option<DeliveredMessage>>, // (source_chain_id, sender, nonce)
+    pub batch_attestations: Mapping<([u8; 32], Address), bool>, // (batch_hash, operator)
+    pub batch_attesters: Mapping<[u8; 32], Vec<Address>>,
+    pub batches: Mapping<[u8; 32], Option<ReleaseBatch>>, // Per root
+    pub claimed: Mapping<([u8; 32], u64), U256>, // (root, index / 256) -> bitmap of claimed leaves
+    pub route_accounting: Mapping<Address, RouteAccounting>, // Per Casper token
 }
 
 #[odra::module]
 impl AnchoreBridge {
-    #[odra(init)]
-    pub fn init(&mut self, amm_address: Address) {
-        self.admin.set(self.env().caller());
-        self.amm_address.set(amm_address);
+    pub fn init(&mut self, pool: Address, operator_registry: Address) {
+        let admin = self.env().caller();
+        self.access.init(admin);
+        self.access.grant_role(Role::FeeManager, admin);
+        self.operator_registry.set(operator_registry);
+        self.register_pool(pool);
+
+        // Epoch 0 starts with the operators already in the registry
+        let operators = self.registry().get_operators();
+        self.operator_sets.set(&0, Some(OperatorSet { operators, threshold: 1 }));
+        self.epoch_started_at.set(self.env().get_block_time());
+        self.epoch_grace_period.set(DEFAULT_EPOCH_GRACE_PERIOD);
+        self.challenge_period.set(DEFAULT_CHALLENGE_PERIOD);
+    }
+
+    // --- Swap Pools ---
+
+    /// Registers `pool` as the AnchorePool releases swap through for its pair,
+    /// replacing any pool previously registered for it
+    pub fn set_pool(&mut self, pool: Address) {
+        self.assert_admin();
+        self.register_pool(pool);
+    }
+
+    // --- Access Control ---
+
+    delegate! {
+        to self.access {
+            fn propose_admin(&mut self, new_admin: Address);
+            fn accept_admin(&mut self);
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn get_admin(&self) -> Address;
+            fn get_pending_admin(&self) -> Option<Address>;
+        }
     }
 
     // --- Operator Management ---
-    
-    pub fn set_operator(&mut self, operator: Address, is_active: bool) {
-        let admin = self.admin.get().unwrap();
-        if self.env().caller() != admin {
-            panic!("Invalid operator");
+
+    /// Proposes the operators and threshold of the next epoch, replacing any
+    /// earlier proposal. Every operator must be in the registry.
+    pub fn propose_operator_set(&mut self, operators: Vec<Address>, threshold: u32) {
+        self.assert_admin();
+        if threshold == 0 || threshold as usize > operators.len() {
+            self.env().revert(Error::InvalidThreshold);
+        }
+        let registry = self.registry();
+        for (i, operator) in operators.iter().enumerate() {
+            if operators[..i].contains(operator) || !registry.is_operator(*operator) {
+                self.env().revert(Error::InvalidOperatorSet);
+            }
+        }
+
+        let epoch = self.get_epoch() + 1;
+        let operator_set = OperatorSet { operators: operators.clone(), threshold };
+        self.proposed_operator_set.set(Some(operator_set));
+        self.env().emit_event(OperatorSetProposed { epoch, operators, threshold });
+    }
+
+    /// Activates the proposed set on signatures over `get_handover_message()`
+    /// from at least `threshold` operators of the current set. Anyone may submit.
+    pub fn handover_operator_set(&mut self, signatures: Vec<(PublicKey, Bytes)>) {
+        let proposed = self.proposed_set();
+        let epoch = self.get_epoch();
+        let message =
+            crypto::handover_message(&self.env().self_address(), epoch + 1, &proposed);
+        let current = self.epoch_operator_set(epoch);
+        self.verify_signatures(&current, &message, &signatures);
+        self.activate_operator_set(proposed, false);
+    }
+
+    /// Activates the proposed set without the current set's signatures, e.g.
+    /// when too few of its operators are left to sign a handover
+    pub fn approve_operator_set(&mut self) {
+        self.assert_admin();
+        let proposed = self.proposed_set();
+        self.activate_operator_set(proposed, true);
+    }
+
+    /// How long payloads of the previous epoch are accepted after a rotation
+    pub fn set_epoch_grace_period(&mut self, grace_period: u64) {
+        self.assert_admin();
+        self.epoch_grace_period.set(grace_period);
+        self.env().emit_event(EpochGracePeriodUpdated { grace_period });
+    }
+
+    // --- Operator Bonds ---
+
+    /// Sets the bond token and terms. The token can't change once set, as
+    /// existing bonds are held in it.
+    pub fn set_bond_config(&mut self, config: BondConfig) {
+        self.assert_admin();
+        if let Some(current) = self.bond_config.get() {
+            if current.token != config.token {
+                self.env().revert(Error::InvalidBondConfig);
+            }
+        }
+        self.bond_config.set(config.clone());
+        self.env().emit_event(BondConfigUpdated {
+            token: config.token,
+            min_bond: config.min_bond,
+            unbonding_delay: config.unbonding_delay,
+        });
+    }
+
+    /// Adds `amount` of the bond token to the caller's bond. Requires an approval.
+    pub fn bond(&mut self, amount: U256) {
+        let config = self.bond_config.get_or_revert_with(Error::BondNotConfigured);
+        if amount == U256::zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
+        let operator = self.env().caller();
+        Cep18ContractRef::new(self.env(), config.token).transfer_from(
+            operator,
+            self.env().self_address(),
+            amount,
+        );
+        let total = self.bonds.get_or_default(&operator) + amount;
+        self.bonds.set(&operator, total);
+        self.total_bonded.set(self.total_bonded.get_or_default() + amount);
+        self.env().emit_event(Bonded { operator, amount, total });
+    }
+
+    /// Moves `amount` of the caller's bond into unbonding. It stops counting
+    /// towards the minimum bond at once but can still be slashed until withdrawn.
+    pub fn start_unbonding(&mut self, amount: U256) {
+        let config = self.bond_config.get_or_revert_with(Error::BondNotConfigured);
+        let operator = self.env().caller();
+        if self.unbondings.get_or_default(&operator).is_some() {
+            self.env().revert(Error::UnbondingInProgress);
+        }
+        let bonded = self.bonds.get_or_default(&operator);
+        if amount == U256::zero() || amount > bonded {
+            self.env().revert(Error::InsufficientBond);
+        }
+        self.bonds.set(&operator, bonded - amount);
+
+        let unlock_at = self.env().get_block_time() + config.unbonding_delay;
+        self.unbondings.set(&operator, Some(Unbonding { amount, unlock_at }));
+        self.env().emit_event(UnbondingStarted { operator, amount, unlock_at });
+    }
+
+    /// Withdraws the caller's unbonded amount once the delay has passed
+    pub fn withdraw_bond(&mut self) {
+        let config = self.bond_config.get_or_revert_with(Error::BondNotConfigured);
+        let operator = self.env().caller();
+        let unbonding = match self.unbondings.get_or_default(&operator) {
+            Some(unbonding) => unbonding,
+            None => self.env().revert(Error::InsufficientBond),
+        };
+        if self.env().get_block_time() < unbonding.unlock_at {
+            self.env().revert(Error::UnbondingInProgress);
+        }
+        self.unbondings.set(&operator, None);
+        self.total_bonded
+            .set(self.total_bonded.get_or_default() - unbonding.amount);
+        Cep18ContractRef::new(self.env(), config.token).transfer(operator, unbonding.amount);
+        self.env().emit_event(BondWithdrawn { operator, amount: unbonding.amount });
+    }
+
+    /// Slashes the whole bond, including any unbonding amount, of the operator
+    /// behind `public_key` and removes it from the registry, which requires
+    /// this bridge to hold OPERATOR_MANAGER there. Requires two valid signatures
+    /// by that key over different payloads for the same transfer, i.e. the
+    /// same (source_chain_id, source_contract, nonce). Re-signing the same
+    /// release in a new epoch is no conflict. The slashed funds go to
+    /// `beneficiary`, such as the treasury or the affected user.
+    pub fn slash(
+        &mut self,
+        public_key: PublicKey,
+        first: ReleasePayload,
+        first_signature: Bytes,
+        second: ReleasePayload,
+        second_signature: Bytes,
+        beneficiary: Address,
+    ) {
+        self.assert_admin();
+        let config = self.bond_config.get_or_revert_with(Error::BondNotConfigured);
+        let second_in_first_epoch = ReleasePayload { epoch: first.epoch, ..second.clone() };
+        if first == second_in_first_epoch || transfer_key(&first) != transfer_key(&second) {
+            self.env().revert(Error::NoConflict);
+        }
+        let bridge = self.env().self_address();
+        for (payload, signature) in [(&first, &first_signature), (&second, &second_signature)] {
+            let message = crypto::release_message(&bridge, payload);
+            if !crypto::verify(&self.env(), &message, signature, &public_key) {
+                self.env().revert(Error::InvalidSignature);
+            }
+        }
+
+        let operator = crypto::signer_address(&public_key);
+        let unbonding = self.unbondings.get_or_default(&operator);
+        let amount = self.bonds.get_or_default(&operator)
+            + unbonding.map(|u| u.amount).unwrap_or_default();
+        if amount == U256::zero() {
+            self.env().revert(Error::InsufficientBond);
+        }
+        self.bonds.set(&operator, U256::zero());
+        self.unbondings.set(&operator, None);
+        self.total_bonded.set(self.total_bonded.get_or_default() - amount);
+        let mut registry = self.registry();
+        if registry.is_operator(operator) {
+            registry.remove_operator(operator);
+        }
+
+        Cep18ContractRef::new(self.env(), config.token).transfer(beneficiary, amount);
+        self.env().emit_event(OperatorSlashed { operator, amount, beneficiary });
+    }
+
+    // --- Token Registry ---
+
+    /// Registers how `token` is custodied. MintBurn tokens must be
+    /// `BridgedToken`s whose bridge is this contract.
+    pub fn set_token_mode(&mut self, token: Address, mode: TokenMode) {
+        self.assert_admin();
+        self.token_modes.set(&token, mode.clone());
+        self.env().emit_event(TokenModeUpdated { token, mode });
+    }
+
+    /// Maps `evm_token` on `source_chain_id` to the Casper `token` it is
+    /// released as. Amounts are converted from `source_decimals` to `local_decimals`.
+    pub fn set_route(
+        &mut self,
+        source_chain_id: U256,
+        evm_token: [u8; 20],
+        token: Address,
+        source_decimals: u8,
+        local_decimals: u8,
+    ) {
+        self.assert_admin();
+        if source_decimals > MAX_DECIMALS || local_decimals > MAX_DECIMALS {
+            self.env().revert(Error::InvalidDecimals);
         }
-        self.operators.set(&operator, is_active);
-        self.env().emit_event(OperatorUpdated { operator, is_active });
+        // A replaced route no longer carries its old token back out
+        if let Some(old) = self.routes.get_or_default(&(source_chain_id, evm_token)) {
+            self.clear_outbound_route(source_chain_id, old.token, evm_token);
+        }
+        let route = TokenRoute { token, source_decimals, local_decimals };
+        self.routes.set(&(source_chain_id, evm_token), Some(route));
+        self.outbound_routes.set(&(source_chain_id, token), Some(evm_token));
+        self.env().emit_event(RouteUpdated {
+            source_chain_id,
+            evm_token,
+            token,
+            source_decimals,
+            local_decimals,
+        });
+    }
+
+    pub fn remove_route(&mut self, source_chain_id: U256, evm_token: [u8; 20]) {
+        self.assert_admin();
+        let route = match self.routes.get_or_default(&(source_chain_id, evm_token)) {
+            Some(route) => route,
+            None => self.env().revert(Error::UnknownRoute),
+        };
+        self.clear_outbound_route(source_chain_id, route.token, evm_token);
+        self.routes.set(&(source_chain_id, evm_token), None);
+        self.env().emit_event(RouteRemoved { source_chain_id, evm_token });
+    }
+
+    // --- Rate Limits ---
+
+    /// Sets the release limits for a Casper `token`. The current window
+    /// usage is kept, so lowering the cap takes effect immediately.
+    pub fn set_limits(&mut self, token: Address, limits: TokenLimits) {
+        self.assert_admin();
+        if limits.window_cap > U256::zero() && limits.window_duration == 0 {
+            self.env().revert(Error::InvalidLimits);
+        }
+        self.limits.set(&token, limits.clone());
+        self.env().emit_event(LimitsUpdated {
+            token,
+            window_cap: limits.window_cap,
+            window_duration: limits.window_duration,
+            max_per_transfer: limits.max_per_transfer,
+            large_transfer_threshold: limits.large_transfer_threshold,
+            delay: limits.delay,
+        });
+    }
+
+    /// Delivers a pending release once its challenge period has passed
+    /// unchallenged. Anyone may call. Still counts against the window cap,
+    /// so it can revert with `RateLimitExceeded` until capacity frees up.
+    pub fn finalize(&mut self, transfer_id: [u8; 32]) {
+        let pending = self.pending_release(transfer_id);
+        if pending.status == ReleaseStatus::Challenged {
+            self.env().revert(Error::ReleaseChallenged);
+        }
+        if self.env().get_block_time() < pending.unlock_at {
+            self.env().revert(Error::ReleaseLocked);
+        }
+        if !self.consume_window(pending.token, pending.amount) {
+            self.env().revert(Error::RateLimitExceeded);
+        }
+        self.remove_pending(&pending);
+
+        self.accrue_fee(
+            pending.token,
+            pending.fee,
+            pending.treasury_fee,
+            pending.lp_fee,
+            &pending.operators,
+        );
+        self.deliver(
+            transfer_id,
+            pending.token,
+            pending.recipient,
+            pending.amount,
+            pending.target_token,
+            pending.min_amount_out,
+        );
+        self.record_release(pending.token, pending.amount, pending.fee);
+        self.env().emit_event(BridgeRelease {
+            recipient: pending.recipient,
+            amount: pending.amount,
+            fee: pending.fee,
+            nonce: pending.nonce,
+            token: pending.token,
+            source_tx_hash: pending.source_tx_hash,
+        });
+    }
+
+    // --- Challenges ---
+
+    /// How long releases from the large transfer threshold on are held
+    /// pending, open to challenges. Already queued releases keep their time.
+    pub fn set_challenge_period(&mut self, period: u64) {
+        self.assert_admin();
+        self.challenge_period.set(period);
+        self.env().emit_event(ChallengePeriodUpdated { period });
+    }
+
+    /// Freezes a pending release during its challenge period. Open to
+    /// guardians and to anyone bonded at least the operator minimum.
+    pub fn challenge(&mut self, transfer_id: [u8; 32]) {
+        let challenger = self.env().caller();
+        let is_bonded_watcher = match self.bond_config.get() {
+            Some(config) => {
+                let bond = self.bonds.get_or_default(&challenger);
+                bond > U256::zero() && bond >= config.min_bond
+            }
+            None => false,
+        };
+        if !self.access.has_role(Role::Guardian, challenger) && !is_bonded_watcher {
+            self.env().revert(Error::NotChallenger);
+        }
+
+        let mut pending = self.pending_release(transfer_id);
+        if pending.status == ReleaseStatus::Challenged {
+            self.env().revert(Error::ReleaseChallenged);
+        }
+        if self.env().get_block_time() >= pending.unlock_at {
+            self.env().revert(Error::ChallengeWindowClosed);
+        }
+        pending.status = ReleaseStatus::Challenged;
+        self.pending.set(&transfer_id, Some(pending));
+        self.env().emit_event(TransferChallenged { transfer_id, challenger });
+    }
+
+    /// Ends the review of a challenged release. An approved release can be
+    /// finalized at once; a rejected one is dropped, its funds stay in
+    /// custody and its transfer stays marked processed.
+    pub fn resolve_challenge(&mut self, transfer_id: [u8; 32], approve: bool) {
+        self.assert_admin();
+        let mut pending = self.pending_release(transfer_id);
+        if pending.status != ReleaseStatus::Challenged {
+            self.env().revert(Error::NotChallenged);
+        }
+        if approve {
+            pending.status = ReleaseStatus::Pending;
+            pending.unlock_at = self.env().get_block_time();
+            self.pending.set(&transfer_id, Some(pending));
+        } else {
+            self.remove_pending(&pending);
+        }
+        self.env().emit_event(ChallengeResolved { transfer_id, approved: approve });
+    }
+
+    // --- Fees ---
+
+    pub fn set_treasury(&mut self, treasury: Address) {
+        self.assert_admin();
+        self.treasury.set(treasury);
+        self.env().emit_event(TreasuryUpdated { treasury });
+    }
+
+    /// Sets the release fee for the route of `evm_token` on `source_chain_id`
+    pub fn set_fee_config(
+        &mut self,
+        source_chain_id: U256,
+        evm_token: [u8; 20],
+        config: FeeConfig,
+    ) {
+        self.assert_role(Role::FeeManager);
+        if config.fee_bps > BPS_DENOMINATOR
+            || config.treasury_share_bps as u64 + config.lp_share_bps as u64
+                > BPS_DENOMINATOR as u64
+        {
+            self.env().revert(Error::InvalidFeeConfig);
+        }
+        self.fee_configs.set(&(source_chain_id, evm_token), config.clone());
+        self.env().emit_event(FeeConfigUpdated {
+            source_chain_id,
+            evm_token,
+            fee_bps: config.fee_bps,
+            min_fee: config.min_fee,
+            treasury_share_bps: config.treasury_share_bps,
+            lp_share_bps: config.lp_share_bps,
+        });
+    }
+
+    /// Pays out the caller's accrued operator fees in `token`
+    pub fn claim_operator_fees(&mut self, token: Address) {
+        let operator = self.env().caller();
+        let amount = self.operator_fees.get_or_default(&(operator, token));
+        if amount == U256::zero() {
+            self.env().revert(Error::NothingToClaim);
+        }
+        self.operator_fees.set(&(operator, token), U256::zero());
+        self.release_reserved_fees(token, amount);
+        self.pay_out(token, operator, amount);
+        self.env().emit_event(FeesClaimed { claimant: operator, token, amount });
+    }
+
+    /// Pays out the treasury's accrued fees in `token` to the treasury. Anyone may call.
+    pub fn claim_treasury_fees(&mut self, token: Address) {
+        let treasury = self.treasury.get_or_revert_with(Error::NotInitialized);
+        let amount = self.treasury_fees.get_or_default(&token);
+        if amount == U256::zero() {
+            self.env().revert(Error::NothingToClaim);
+        }
+        self.treasury_fees.set(&token, U256::zero());
+        self.release_reserved_fees(token, amount);
+        self.pay_out(token, treasury, amount);
+        self.env().emit_event(FeesClaimed { claimant: treasury, token, amount });
+    }
+
+    // --- Liquidity ---
+
+    /// Deposits `amount` of a LockUnlock `token` for shares of its liquidity,
+    /// see `get_liquidity`. Requires an approval.
+    pub fn add_liquidity(&mut self, token: Address, amount: U256) {
+        if self.token_mode(token) != TokenMode::LockUnlock {
+            self.env().revert(Error::UnsupportedToken);
+        }
+        let provider = self.env().caller();
+        self.liquidity.deposit(token, provider, amount);
+        Cep18ContractRef::new(self.env(), token).transfer_from(
+            provider,
+            self.env().self_address(),
+            amount,
+        );
+    }
+
+    /// Burns `shares` of the caller's `token` liquidity for their part of it.
+    /// Funds held for pending releases, unclaimed fees or bonds can't be
+    /// withdrawn.
+    pub fn remove_liquidity(&mut self, token: Address, shares: U256) {
+        let provider = self.env().caller();
+        let amount = self.liquidity.withdraw(token, provider, shares);
+        if amount > self.unreserved_balance(token) {
+            self.env().revert(LiquidityError::InsufficientLiquidity);
+        }
+        Cep18ContractRef::new(self.env(), token).transfer(provider, amount);
     }
 
     // --- Bridging Logic ---
 
-    /// Unlocks bridged funds. 
-    /// If `swap_to_token` is set, it routes funds through the AMM first.
-    pub fn receive_from_bridge(
+    /// Records the caller's attestation for a release payload.
+    /// Attestations are tallied per unique operator on the payload hash, and
+    /// the funds are released directly once the threshold of the payload's
+    /// epoch is reached.
+    pub fn attest(&mut self, payload: ReleasePayload) {
+        let caller = self.env().caller();
+        let operator_set = self.epoch_operator_set(payload.epoch);
+        if !self.is_set_operator(&operator_set, caller) {
+            self.env().revert(Error::NotOperator);
+        }
+        self.assert_releasable(&payload);
+
+        let payload_hash = self.payload_hash(&payload);
+        if self.attestations.get_or_default(&(payload_hash, caller)) {
+            self.env().revert(Error::AlreadyAttested);
+        }
+        self.attestations.set(&(payload_hash, caller), true);
+
+        let mut attesters = self.attesters.get_or_default(&payload_hash);
+        attesters.push(caller);
+        self.attesters.set(&payload_hash, attesters.clone());
+        let count = attesters.len() as u32;
+
+        self.env().emit_event(TransferAttested {
+            transfer_id: payload.transfer_id,
+            payload_hash,
+            operator: caller,
+            attestations: count,
+        });
+
+        if count >= operator_set.threshold {
+            self.release(&payload, &attesters);
+        }
+    }
+
+    /// Releases a payload signed by at least `threshold` distinct operators
+    /// of its epoch. Anyone may submit; each signature is a bytesrepr-encoded
+    /// Ed25519 or secp256k1 `Signature` over `get_release_message(payload)`.
+    pub fn receive_with_signatures(
         &mut self,
-        recipient: Address,
-        amount: U256,
-        token_address: Address, // This is the token the Bridge "holds" (e.g., wUSDC)
-        nonce: U256,
-        should_swap: bool, // If true, swap wUSDC -> CSPR before sending
+        payload: ReleasePayload,
+        signatures: Vec<(PublicKey, Bytes)>,
     ) {
+        let operator_set = self.epoch_operator_set(payload.epoch);
+        self.assert_releasable(&payload);
+
+        let message = crypto::release_message(&self.env().self_address(), &payload);
+        let signers = self.verify_signatures(&operator_set, &message, &signatures);
+        self.release(&payload, &signers);
+    }
+
+    /// Unlocks bridged funds on a single operator's call.
+    /// Only available while the threshold is 1; otherwise use `attest`.
+    pub fn receive_from_bridge(&mut self, payload: ReleasePayload) {
         // 1. Security Checks
-        if self.processed_nonces.get_or_default(&nonce) {
-            panic!("Invalid token");
+        let caller = self.env().caller();
+        let operator_set = self.epoch_operator_set(payload.epoch);
+        if !self.is_set_operator(&operator_set, caller) {
+            self.env().revert(Error::NotOperator);
+        }
+
+        if operator_set.threshold > 1 {
+            self.env().revert(Error::AttestationRequired);
         }
-        
+
+        self.assert_releasable(&payload);
+        self.release(&payload, &[caller]);
+    }
+
+    // --- Batched Releases ---
+
+    /// Records the caller's attestation for a Merkle root over `leaf_count`
+    /// releases, in the current epoch. Once `threshold` operators attested
+    /// the same root and count, the batch is posted and its leaves can be
+    /// claimed with `claim`.
+    pub fn attest_batch(&mut self, root: [u8; 32], leaf_count: u64) {
         let caller = self.env().caller();
-        if !self.operators.get_or_default(&caller) {
-            panic!("Insufficient balance");
-        }
-
-        self.processed_nonces.set(&nonce, true);
-
-        // 2. Execution Logic
-        if should_swap {
-            // OPTION A: Cross-Chain Swap
-            // The Bridge contract needs to approve the AMM to spend its tokens first.
-            // (Assumes the Bridge contract holds a balance of `token_address`)
-            
-            // Note: In Odra/Casper, contract-to-contract calls work, 
-            // but the Bridge needs to hold the funds to give them to the AMM.
-            
-            let amm = self.amm_address.get().unwrap();
-            
-            // Call AMM to swap and send result to User
-            // Note: We need to transfer funds to AMM first or approve it.
-            // For simplicity: We assume Bridge holds funds, transfers to AMM, AMM sends to user.
-            AnchoreAMMContractRef::new(self.env(), amm).swap_exact_tokens(amount, token_address, recipient);
+        let epoch = self.get_epoch();
+        let operator_set = self.epoch_operator_set(epoch);
+        if !self.is_set_operator(&operator_set, caller) {
+            self.env().revert(Error::NotOperator);
+        }
+        if leaf_count == 0 {
+            self.env().revert(Error::EmptyBatch);
+        }
+        if self.batches.get_or_default(&root).is_some() {
+            self.env().revert(Error::AlreadyAttested);
+        }
+
+        let bytes = (epoch, root, leaf_count)
+            .to_bytes()
+            .unwrap_or_else(|_| self.env().revert(Error::InvalidPayload));
+        let batch_hash = self.env().hash(bytes);
+        if self.batch_attestations.get_or_default(&(batch_hash, caller)) {
+            self.env().revert(Error::AlreadyAttested);
+        }
+        self.batch_attestations.set(&(batch_hash, caller), true);
+        let mut attesters = self.batch_attesters.get_or_default(&batch_hash);
+        attesters.push(caller);
+        self.batch_attesters.set(&batch_hash, attesters.clone());
+        let count = attesters.len() as u32;
+        self.env().emit_event(ReleaseBatchAttested {
+            root,
+            operator: caller,
+            attestations: count,
+        });
+
+        if count >= operator_set.threshold {
+            self.batches.set(
+                &root,
+                Some(ReleaseBatch { root, leaf_count, epoch, operators: attesters }),
+            );
+            self.env().emit_event(ReleaseBatchPosted { root, leaf_count, epoch });
+        }
+    }
+
+    /// Releases leaf `index` of a posted batch. Anyone may call; the funds
+    /// go to the leaf's recipient. `proof` holds the sibling hashes from the
+    /// leaf up to `root`. Claims go through the same route, fee and limit
+    /// checks as other releases, and the transfer's nonce can't be released
+    /// again by any path. Batches expire with their epoch: once the grace
+    /// period after the next rotation ends, their leaves can't be claimed.
+    pub fn claim(&mut self, root: [u8; 32], index: u64, proof: Vec<[u8; 32]>, leaf: ReleasePayload) {
+        let batch = match self.batches.get_or_default(&root) {
+            Some(batch) => batch,
+            None => self.env().revert(Error::UnknownBatch),
+        };
+        if !self.is_epoch_accepted(batch.epoch) {
+            self.env().revert(Error::BatchExpired);
+        }
+        if index >= batch.leaf_count {
+            self.env().revert(Error::InvalidProof);
+        }
+        if self.is_claimed(root, index) {
+            self.env().revert(Error::AlreadyClaimed);
+        }
+        let leaf_hash = crypto::release_leaf(&self.env(), index, &leaf);
+        if !crypto::verify_proof(&self.env(), &root, leaf_hash, &proof) {
+            self.env().revert(Error::InvalidProof);
+        }
+        self.assert_releasable(&leaf);
+
+        let word = index / 256;
+        let bits = self.claimed.get_or_default(&(root, word));
+        self.claimed.set(&(root, word), bits | (U256::one() << (index % 256)));
+        self.env().emit_event(ReleaseClaimed {
+            root,
+            index,
+            transfer_id: leaf.transfer_id,
+        });
+        self.release(&leaf, &batch.operators);
+    }
+
+    // --- Messages (EVM -> Casper) ---
+
+    /// Allows or disallows cross-chain messages to call `entry_point` on
+    /// `target`
+    pub fn set_message_target(&mut self, target: Address, entry_point: String, allowed: bool) {
+        self.assert_admin();
+        self.message_targets.set(&(target, entry_point.clone()), allowed);
+        self.env().emit_event(MessageTargetUpdated { target, entry_point, allowed });
+    }
+
+    /// Records the caller's attestation for a message, in the current epoch.
+    /// Once `threshold` operators attested the same message it is delivered:
+    /// stored for `execute_message` and its nonce marked used.
+    pub fn receive_message(
+        &mut self,
+        source_chain_id: U256,
+        sender: [u8; 20],
+        nonce: U256,
+        target: Address,
+        entry_point: String,
+        args: Bytes,
+    ) {
+        let caller = self.env().caller();
+        let epoch = self.get_epoch();
+        let operator_set = self.epoch_operator_set(epoch);
+        if !self.is_set_operator(&operator_set, caller) {
+            self.env().revert(Error::NotOperator);
+        }
+        if !self.is_message_target(target, entry_point.clone()) {
+            self.env().revert(Error::TargetNotAllowed);
+        }
+        if self.messages.get_or_default(&(source_chain_id, sender, nonce)).is_some() {
+            self.env().revert(Error::NonceAlreadyProcessed);
+        }
+
+        let message = CrossChainMessage { source_chain_id, sender, nonce, target, entry_point, args };
+        let bytes = (epoch, message.clone())
+            .to_bytes()
+            .unwrap_or_else(|_| self.env().revert(Error::InvalidPayload));
+        let message_hash = self.env().hash(bytes);
+        if self.message_attestations.get_or_default(&(message_hash, caller)) {
+            self.env().revert(Error::AlreadyAttested);
+        }
+        self.message_attestations.set(&(message_hash, caller), true);
+        let mut attesters = self.message_attesters.get_or_default(&message_hash);
+        attesters.push(caller);
+        self.message_attesters.set(&message_hash, attesters.clone());
+        let count = attesters.len() as u32;
+        self.env().emit_event(MessageAttested {
+            source_chain_id,
+            sender,
+            nonce,
+            operator: caller,
+            attestations: count,
+        });
+
+        if count >= operator_set.threshold {
+            let entry_point = message.entry_point.clone();
+            self.messages.set(
+                &(source_chain_id, sender, nonce),
+                Some(DeliveredMessage { message, status: MessageStatus::Delivered, attempts: 0 }),
+            );
+            self.env().emit_event(MessageReceived {
+                source_chain_id,
+                sender,
+                nonce,
+                target,
+                entry_point,
+            });
+        }
+    }
+
+    /// Calls the target of a delivered message, or retries a failed one.
+    /// Anyone may call. The target is called with the message args plus
+    /// `source_chain_id` and `sender`, and must return a bool: false records
+    /// the message as failed. A reverting target reverts only this call, as
+    /// Casper can't catch reverts, and leaves the message retryable.
+    pub fn execute_message(&mut self, source_chain_id: U256, sender: [u8; 20], nonce: U256) {
+        let key = (source_chain_id, sender, nonce);
+        let mut delivered = match self.messages.get_or_default(&key) {
+            Some(delivered) => delivered,
+            None => self.env().revert(Error::UnknownMessage),
+        };
+        if delivered.status == MessageStatus::Executed {
+            self.env().revert(Error::MessageAlreadyExecuted);
+        }
+        let message = delivered.message.clone();
+        if !self.is_message_target(message.target, message.entry_point.clone()) {
+            self.env().revert(Error::TargetNotAllowed);
+        }
+
+        // Marked executed up front, so the target can't re-enter and run it twice
+        delivered.attempts += 1;
+        delivered.status = MessageStatus::Executed;
+        self.messages.set(&key, Some(delivered.clone()));
+
+        let success = match message_call_args(&message) {
+            Some(args) => self.env().call_contract::<bool>(
+                message.target,
+                CallDef::new(message.entry_point, true, args),
+            ),
+            None => false, // Malformed args can't ever succeed
+        };
+        if !success {
+            delivered.status = MessageStatus::Failed;
+            self.messages.set(&key, Some(delivered.clone()));
+        }
+        self.env().emit_event(MessageExecuted {
+            source_chain_id,
+            sender,
+            nonce,
+            success,
+            attempt: delivered.attempts,
+        });
+    }
+
+    // --- Outbound (Casper -> EVM) ---
 
+    /// Escrows (LockUnlock) or burns (MintBurn) `amount` of `token` from the
+    /// caller for release on an EVM chain, which needs a route for `token`.
+    /// The emitted `BridgeRequested` carries exactly the fields
+    /// `AnchoreVault.bridgeIn` hashes: amount, recipient, nonce and chain id,
+    /// with the amount converted to the EVM token's decimals.
+    pub fn bridge_out(
+        &mut self,
+        token: Address,
+        amount: U256,
+        evm_recipient: [u8; 20],
+        dest_chain_id: U256,
+    ) {
+        if amount == U256::zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
+        if evm_recipient == [0u8; 20] {
+            self.env().revert(Error::InvalidRecipient);
+        }
+
+        let evm_amount = self.outbound_amount(dest_chain_id, token, amount);
+        let sender = self.env().caller();
+        match self.token_mode(token) {
+            TokenMode::LockUnlock => Cep18ContractRef::new(self.env(), token)
+                .transfer_from(sender, self.env().self_address(), amount),
+            TokenMode::MintBurn => {
+                MintableTokenContractRef::new(self.env(), token).burn(sender, amount)
+            }
+        }
+
+        let nonce = self.outbound_nonce.get_or_default();
+        self.outbound_nonce.set(nonce + 1);
+        let mut accounting = self.get_route_accounting(token);
+        accounting.bridged_out += amount;
+        self.route_accounting.set(&token, accounting);
+
+        self.env().emit_event(BridgeRequested {
+            sender,
+            token,
+            amount: evm_amount,
+            evm_recipient,
+            nonce,
+            dest_chain_id,
+        });
+    }
+
+    // --- Views ---
+
+
+    /// AnchorePool registered for a token pair, in either order
+    pub fn get_pool(&self, token_a: Address, token_b: Address) -> Option<Address> {
+        self.pools.get_or_default(&sort_pair(token_a, token_b))
+    }
+
+    /// Threshold of the current epoch
+    pub fn get_threshold(&self) -> u32 {
+        self.get_operator_set(self.get_epoch())
+            .map(|operator_set| operator_set.threshold)
+            .unwrap_or_default()
+    }
+
+    pub fn get_epoch(&self) -> u32 {
+        self.epoch.get_or_default()
+    }
+
+    pub fn get_operator_set(&self, epoch: u32) -> Option<OperatorSet> {
+        self.operator_sets.get_or_default(&epoch)
+    }
+
+    pub fn get_proposed_operator_set(&self) -> Option<OperatorSet> {
+        self.proposed_operator_set.get_or_default()
+    }
+
+    /// Block time the current epoch started at
+    pub fn get_epoch_started_at(&self) -> u64 {
+        self.epoch_started_at.get_or_default()
+    }
+
+    pub fn get_epoch_grace_period(&self) -> u64 {
+        self.epoch_grace_period.get_or_default()
+    }
+
+    /// Canonical message the current operators sign for `handover_operator_set`
+    pub fn get_handover_message(&self) -> Bytes {
+        crypto::handover_message(
+            &self.env().self_address(),
+            self.get_epoch() + 1,
+            &self.proposed_set(),
+        )
+    }
+
+    pub fn get_operator_registry(&self) -> Address {
+        self.operator_registry.get_or_revert_with(Error::NotInitialized)
+    }
+
+    /// Whether `operator` is in the current epoch's set, registered and
+    /// bonded, i.e. may release
+    pub fn is_operator(&self, operator: Address) -> bool {
+        match self.get_operator_set(self.get_epoch()) {
+            Some(operator_set) => self.is_set_operator(&operator_set, operator),
+            None => false,
+        }
+    }
+
+    pub fn get_bond_config(&self) -> Option<BondConfig> {
+        self.bond_config.get()
+    }
+
+    /// Active bond of `operator`, excluding any amount being unbonded
+    pub fn get_bond(&self, operator: Address) -> U256 {
+        self.bonds.get_or_default(&operator)
+    }
+
+    pub fn get_unbonding(&self, operator: Address) -> Option<Unbonding> {
+        self.unbondings.get_or_default(&operator)
+    }
+
+    /// Whether the transfer `nonce` from `source_contract` on `source_chain_id`
+    /// was already released. Nonces are only unique per source vault.
+    pub fn is_processed(
+        &self,
+        source_chain_id: U256,
+        source_contract: [u8; 20],
+        nonce: U256,
+    ) -> bool {
+        self.processed
+            .get_or_default(&(source_chain_id, source_contract, nonce))
+    }
+
+    /// Custody mode of a registered token, None if unsupported
+    pub fn get_token_mode(&self, token: Address) -> Option<TokenMode> {
+        self.token_modes.get(&token)
+    }
+
+    /// Casper token and decimals an EVM token is released as, None if unrouted
+    pub fn get_route(&self, source_chain_id: U256, evm_token: [u8; 20]) -> Option<TokenRoute> {
+        self.routes.get_or_default(&(source_chain_id, evm_token))
+    }
+
+    /// EVM token `token` is bridged out as to `dest_chain_id`
+    pub fn get_outbound_route(&self, dest_chain_id: U256, token: Address) -> Option<[u8; 20]> {
+        self.outbound_routes.get_or_default(&(dest_chain_id, token))
+    }
+
+    /// Release limits of `token`, None if unlimited
+    pub fn get_limits(&self, token: Address) -> Option<TokenLimits> {
+        self.limits.get(&token)
+    }
+
+    /// Hold time of releases from the large transfer threshold on
+    pub fn get_challenge_period(&self) -> u64 {
+        self.challenge_period.get_or_default()
+    }
+
+    /// Amount of `token` counted against the window cap now
+    pub fn get_window_usage(&self, token: Address) -> U256 {
+        let limits = self.limits.get(&token).unwrap_or_default();
+        self.current_usage(token, &limits).used
+    }
+
+    /// Amount of `token` that can be released now, U256::MAX if uncapped
+    pub fn get_remaining_capacity(&self, token: Address) -> U256 {
+        let limits = self.limits.get(&token).unwrap_or_default();
+        if limits.window_cap == U256::zero() {
+            return U256::MAX;
+        }
+        let used = self.current_usage(token, &limits).used;
+        limits.window_cap.saturating_sub(used)
+    }
+
+    pub fn get_pending_release(&self, transfer_id: [u8; 32]) -> Option<PendingRelease> {
+        self.pending.get_or_default(&transfer_id)
+    }
+
+    /// All pending and challenged releases, oldest first
+    pub fn get_pending_releases(&self) -> Vec<PendingRelease> {
+        self.pending_ids
+            .get_or_default()
+            .iter()
+            .filter_map(|transfer_id| self.pending.get_or_default(transfer_id))
+            .collect()
+    }
+
+
+    pub fn get_treasury(&self) -> Option<Address> {
+        self.treasury.get()
+    }
+
+    pub fn get_fee_config(&self, source_chain_id: U256, evm_token: [u8; 20]) -> FeeConfig {
+        self.fee_configs.get_or_default(&(source_chain_id, evm_token))
+    }
+
+    /// Fees `operator` can claim in `token`
+    pub fn get_operator_fees(&self, operator: Address, token: Address) -> U256 {
+        self.operator_fees.get_or_default(&(operator, token))
+    }
+
+    /// Fees the treasury can claim in `token`
+    pub fn get_treasury_fees(&self, token: Address) -> U256 {
+        self.treasury_fees.get_or_default(&token)
+    }
+
+    /// Value of `token` owned by liquidity providers: their deposits and
+    /// fee share, less withdrawals
+    pub fn get_liquidity(&self, token: Address) -> U256 {
+        self.liquidity.get_value(token)
+    }
+
+    pub fn get_liquidity_shares(&self, token: Address, provider: Address) -> U256 {
+        self.liquidity.get_shares(token, provider)
+    }
+
+    pub fn get_total_liquidity_shares(&self, token: Address) -> U256 {
+        self.liquidity.get_total_shares(token)
+    }
+
+    pub fn is_message_target(&self, target: Address, entry_point: String) -> bool {
+        self.message_targets.get_or_default(&(target, entry_point))
+    }
+
+    /// A delivered message with its execution status
+    pub fn get_message(
+        &self,
+        source_chain_id: U256,
+        sender: [u8; 20],
+        nonce: U256,
+    ) -> Option<DeliveredMessage> {
+        self.messages.get_or_default(&(source_chain_id, sender, nonce))
+    }
+
+    /// Cumulative released, fee and outbound amounts of `token`
+    pub fn get_route_accounting(&self, token: Address) -> RouteAccounting {
+        self.route_accounting.get_or_default(&token)
+    }
+
+    /// A posted release batch
+    pub fn get_batch(&self, root: [u8; 32]) -> Option<ReleaseBatch> {
+        self.batches.get_or_default(&root)
+    }
+
+    pub fn is_claimed(&self, root: [u8; 32], index: u64) -> bool {
+        let bits = self.claimed.get_or_default(&(root, index / 256));
+        bits & (U256::one() << (index % 256)) != U256::zero()
+    }
+
+    /// Leaf hash of `payload` at `index` of a batch, for building the tree
+    pub fn get_release_leaf(&self, index: u64, payload: ReleasePayload) -> [u8; 32] {
+        crypto::release_leaf(&self.env(), index, &payload)
+    }
+
+    /// Nonce the next `bridge_out` will use
+    pub fn get_outbound_nonce(&self) -> U256 {
+        self.outbound_nonce.get_or_default()
+    }
+
+    /// Canonical message operators sign for `receive_with_signatures`
+    pub fn get_release_message(&self, payload: ReleasePayload) -> Bytes {
+        crypto::release_message(&self.env().self_address(), &payload)
+    }
+
+    /// Number of operators that attested to exactly this payload
+    pub fn get_attestation_count(&self, payload: ReleasePayload) -> u32 {
+        self.attesters.get_or_default(&self.payload_hash(&payload)).len() as u32
+    }
+
+    // --- Internal Helpers ---
+
+    fn register_pool(&mut self, pool: Address) {
+        let (token_a, token_b) = AnchorePoolContractRef::new(self.env(), pool).get_tokens();
+        self.pools.set(&sort_pair(token_a, token_b), Some(pool));
+        self.env().emit_event(PoolUpdated { token_a, token_b, pool });
+    }
+
+    fn assert_admin(&self) {
+        self.assert_role(Role::Admin);
+    }
+
+    fn assert_role(&self, role: Role) {
+        if !self.access.has_role(role, self.env().caller()) {
+            self.env().revert(Error::Unauthorized);
+        }
+    }
+
+    fn registry(&self) -> OperatorRegistryInterfaceContractRef {
+        let registry = self.operator_registry.get_or_revert_with(Error::NotInitialized);
+        OperatorRegistryInterfaceContractRef::new(self.env(), registry)
+    }
+
+    /// Whether `epoch` is the current epoch, or the previous one during the
+    /// grace period after a rotation
+    fn is_epoch_accepted(&self, epoch: u32) -> bool {
+        let current = self.get_epoch();
+        let in_grace = epoch + 1 == current
+            && self.env().get_block_time()
+                < self.get_epoch_started_at() + self.get_epoch_grace_period();
+        epoch == current || in_grace
+    }
+
+    /// Operator set payloads of `epoch` are checked against, if accepted
+    fn epoch_operator_set(&self, epoch: u32) -> OperatorSet {
+        if !self.is_epoch_accepted(epoch) {
+            self.env().revert(Error::EpochNotAccepted);
+        }
+        match self.get_operator_set(epoch) {
+            Some(operator_set) => operator_set,
+            None => self.env().revert(Error::EpochNotAccepted),
+        }
+    }
+
+    fn proposed_set(&self) -> OperatorSet {
+        match self.proposed_operator_set.get_or_default() {
+            Some(operator_set) => operator_set,
+            None => self.env().revert(Error::NoProposedOperatorSet),
+        }
+    }
+
+    fn activate_operator_set(&mut self, operator_set: OperatorSet, approved_by_admin: bool) {
+        let epoch = self.get_epoch() + 1;
+        self.epoch.set(epoch);
+        self.operator_sets.set(&epoch, Some(operator_set.clone()));
+        self.epoch_started_at.set(self.env().get_block_time());
+        self.proposed_operator_set.set(None);
+        self.env().emit_event(EpochActivated {
+            epoch,
+            operators: operator_set.operators,
+            threshold: operator_set.threshold,
+            approved_by_admin,
+        });
+    }
+
+    /// Checks that `signatures` come from at least `threshold` distinct
+    /// operators of `operator_set`, returns the signers
+    fn verify_signatures(
+        &self,
+        operator_set: &OperatorSet,
+        message: &Bytes,
+        signatures: &[(PublicKey, Bytes)],
+    ) -> Vec<Address> {
+        let mut signers: Vec<Address> = Vec::new();
+        for (public_key, signature) in signatures.iter() {
+            let signer = crypto::signer_address(public_key);
+            if !self.is_set_operator(operator_set, signer) {
+                self.env().revert(Error::NotOperator);
+            }
+            if signers.contains(&signer) {
+                self.env().revert(Error::DuplicateSigner);
+            }
+            if !crypto::verify(&self.env(), message, signature, public_key) {
+                self.env().revert(Error::InvalidSignature);
+            }
+            signers.push(signer);
+        }
+
+        if (signers.len() as u32) < operator_set.threshold {
+            self.env().revert(Error::ThresholdNotMet);
+        }
+        signers
+    }
+
+    /// In `operator_set`, and still registered and bonded
+    fn is_set_operator(&self, operator_set: &OperatorSet, operator: Address) -> bool {
+        operator_set.operators.contains(&operator) && self.is_active_operator(operator)
+    }
+
+    /// Registered, and bonded at least the minimum if bonding is configured
+    fn is_active_operator(&self, operator: Address) -> bool {
+        if !self.registry().is_operator(operator) {
+            return false;
+        }
+        match self.bond_config.get() {
+            Some(config) => self.bonds.get_or_default(&operator) >= config.min_bond,
+            None => true,
+        }
+    }
+
+    fn payload_hash(&self, payload: &ReleasePayload) -> [u8; 32] {
+        let bytes = payload
+            .to_bytes()
+            .unwrap_or_else(|_| self.env().revert(Error::InvalidPayload));
+        self.env().hash(bytes)
+    }
+
+    /// Checks shared by every release path
+    fn assert_releasable(&self, payload: &ReleasePayload) {
+        if self.processed.get_or_default(&transfer_key(payload)) {
+            self.env().revert(Error::NonceAlreadyProcessed);
+        }
+        if payload.amount == U256::zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
+        self.recipient(payload);
+    }
+
+    /// The account a payload releases to; reverts on malformed recipients
+    /// rather than guessing
+    fn recipient(&self, payload: &ReleasePayload) -> Address {
+        match crypto::recipient_address(&payload.recipient) {
+            Ok(recipient) => recipient,
+            Err(RecipientError::UnknownTag) => self.env().revert(Error::UnknownRecipientTag),
+            Err(RecipientError::Malformed) => self.env().revert(Error::MalformedRecipient),
+        }
+    }
+
+    /// Resolves the Casper token and local amount for a payload
+    fn resolve_route(&self, payload: &ReleasePayload) -> (Address, U256) {
+        let route = match self.routes.get_or_default(&(payload.source_chain_id, payload.evm_token)) {
+            Some(route) => route,
+            None => self.env().revert(Error::UnknownRoute),
+        };
+
+        let amount =
+            match convert_decimals(payload.amount, route.source_decimals, route.local_decimals) {
+                Some(amount) => amount,
+                None => self.env().revert(Error::InexactAmount),
+            };
+        (route.token, amount)
+    }
+
+    /// `amount` of `token` in the decimals of the EVM token it is bridged
+    /// out as to `dest_chain_id`
+    fn outbound_amount(&self, dest_chain_id: U256, token: Address, amount: U256) -> U256 {
+        let route = self
+            .get_outbound_route(dest_chain_id, token)
+            .and_then(|evm_token| self.get_route(dest_chain_id, evm_token));
+        let route = match route {
+            Some(route) => route,
+            None => self.env().revert(Error::UnknownRoute),
+        };
+        match convert_decimals(amount, route.local_decimals, route.source_decimals) {
+            Some(amount) => amount,
+            None => self.env().revert(Error::InexactAmount),
+        }
+    }
+
+    fn clear_outbound_route(&mut self, chain_id: U256, token: Address, evm_token: [u8; 20]) {
+        if self.get_outbound_route(chain_id, token) == Some(evm_token) {
+            self.outbound_routes.set(&(chain_id, token), None);
+        }
+    }
+
+    fn token_mode(&self, token: Address) -> TokenMode {
+        match self.token_modes.get(&token) {
+            Some(mode) => mode,
+            None => self.env().revert(Error::UnsupportedToken),
+        }
+    }
+
+    /// Usage as of now. Usage decays linearly, by `window_cap` per
+    /// `window_duration`, so capacity frees up gradually instead of all at
+    /// once at a window boundary.
+    fn current_usage(&self, token: Address, limits: &TokenLimits) -> WindowUsage {
+        let usage = self.window_usage.get(&token).unwrap_or_default();
+        let now = self.env().get_block_time();
+        if limits.window_duration == 0 {
+            return WindowUsage { updated_at: now, used: U256::zero() };
+        }
+        let elapsed = now.saturating_sub(usage.updated_at);
+        let decayed =
+            limits.window_cap * U256::from(elapsed) / U256::from(limits.window_duration);
+        WindowUsage { updated_at: now, used: usage.used.saturating_sub(decayed) }
+    }
+
+    /// Books `amount` against the window cap, false if it doesn't fit
+    fn consume_window(&mut self, token: Address, amount: U256) -> bool {
+        let limits = self.limits.get(&token).unwrap_or_default();
+        if limits.window_cap == U256::zero() {
+            return true;
+        }
+        let mut usage = self.current_usage(token, &limits);
+        if usage.used + amount > limits.window_cap {
+            self.env().emit_event(RateLimitHit {
+                token,
+                amount,
+                window_used: usage.used,
+                window_cap: limits.window_cap,
+            });
+            return false;
+        }
+        usage.used += amount;
+        self.window_usage.set(&token, usage);
+        true
+    }
+
+    /// The route's fee on `amount`, and the treasury's and LPs' shares of it
+    fn quote_fee(&self, payload: &ReleasePayload, amount: U256) -> (U256, U256, U256) {
+        let config = self
+            .fee_configs
+            .get_or_default(&(payload.source_chain_id, payload.evm_token));
+        let bps_fee = amount * U256::from(config.fee_bps) / U256::from(BPS_DENOMINATOR);
+        let fee = bps_fee.max(config.min_fee);
+        if fee >= amount {
+            self.env().revert(Error::FeeExceedsAmount);
+        }
+        let treasury_share =
+            fee * U256::from(config.treasury_share_bps) / U256::from(BPS_DENOMINATOR);
+        let lp_share = fee * U256::from(config.lp_share_bps) / U256::from(BPS_DENOMINATOR);
+        (fee, treasury_share, lp_share)
+    }
+
+    /// Credits `fee` to `operators`, the treasury and liquidity providers
+    fn accrue_fee(
+        &mut self,
+        token: Address,
+        fee: U256,
+        treasury_share: U256,
+        lp_share: U256,
+        operators: &[Address],
+    ) {
+        if fee == U256::zero() {
+            return;
+        }
+        // Each operator gets an equal cut, rounding dust and any LP share
+        // without providers go to the treasury
+        let operator_count = U256::from(operators.len());
+        let per_operator = (fee - treasury_share - lp_share) / operator_count;
+        let lp_share = if self.liquidity.get_total_shares(token) == U256::zero() {
+            U256::zero()
         } else {
-            // OPTION B: Direct Bridge (Standard)
-            // Just release the token to the user
-            Cep18ContractRef::new(self.env(), token_address).transfer(recipient, amount);
+            lp_share
+        };
+        for operator in operators {
+            let owed = self.operator_fees.get_or_default(&(*operator, token));
+            self.operator_fees.set(&(*operator, token), owed + per_operator);
+        }
+        let treasury_share = fee - lp_share - per_operator * operator_count;
+        let accrued = self.treasury_fees.get_or_default(&token);
+        self.treasury_fees.set(&token, accrued + treasury_share);
+        let reserved = self.reserved_fees.get_or_default(&token);
+        self.reserved_fees.set(&token, reserved + fee - lp_share);
+        self.liquidity.add_value(token, lp_share);
+    }
+
+    /// The bridge's balance of `token` not reserved for pending releases,
+    /// unclaimed fees or bonds
+    fn unreserved_balance(&self, token: Address) -> U256 {
+        let mut reserved =
+            self.reserved_fees.get_or_default(&token) + self.pending_amounts.get_or_default(&token);
+        if let Some(config) = self.bond_config.get() {
+            if config.token == token {
+                reserved += self.total_bonded.get_or_default();
+            }
         }
+        Cep18ContractRef::new(self.env(), token)
+            .balance_of(self.env().self_address())
+            .saturating_sub(reserved)
+    }
+
+    /// Checks the unreserved custody covers a LockUnlock release, and debits
+    /// from the providers' value what the rest of the custody can't cover
+    fn draw_custody(&mut self, token: Address, amount: U256) {
+        let unreserved = self.unreserved_balance(token);
+        if amount > unreserved {
+            self.env().revert(LiquidityError::InsufficientLiquidity);
+        }
+        let value = self.liquidity.get_value(token);
+        let from_liquidity = amount.saturating_sub(unreserved.saturating_sub(value));
+        self.liquidity.remove_value(token, from_liquidity);
+    }
 
+    fn release_reserved_fees(&mut self, token: Address, amount: U256) {
+        let reserved = self.reserved_fees.get_or_default(&token);
+        self.reserved_fees.set(&token, reserved - amount);
+    }
+
+    fn pending_release(&self, transfer_id: [u8; 32]) -> PendingRelease {
+        match self.pending.get_or_default(&transfer_id) {
+            Some(pending) => pending,
+            None => self.env().revert(Error::NotPending),
+        }
+    }
+
+    fn remove_pending(&mut self, pending: &PendingRelease) {
+        let transfer_id = pending.transfer_id;
+        self.pending.set(&transfer_id, None);
+        let mut ids = self.pending_ids.get_or_default();
+        ids.retain(|id| *id != transfer_id);
+        self.pending_ids.set(ids);
+        let held = self.pending_amounts.get_or_default(&pending.token);
+        self.pending_amounts
+            .set(&pending.token, held - pending.amount - pending.fee);
+    }
+
+    /// Marks the transfer processed and deducts the fee, then delivers the
+    /// funds or holds them pending if the release is large or over the window
+    /// cap. `operators` are the attesters or signers credited with the fee.
+    fn release(&mut self, payload: &ReleasePayload, operators: &[Address]) {
+        let (token_address, gross_amount) = self.resolve_route(payload);
+        let (recipient, nonce) = (self.recipient(payload), payload.nonce);
+        self.token_mode(token_address); // Reverts for unsupported tokens
+        self.processed.set(&transfer_key(payload), true);
+        let (fee, treasury_fee, lp_fee) = self.quote_fee(payload, gross_amount);
+        let amount = gross_amount - fee;
+
+        let limits = self.limits.get(&token_address).unwrap_or_default();
+        if limits.max_per_transfer > U256::zero() && amount > limits.max_per_transfer {
+            self.env().revert(Error::TransferAboveMaximum);
+        }
+
+        let is_large = limits.large_transfer_threshold > U256::zero()
+            && amount >= limits.large_transfer_threshold;
+        if is_large || !self.consume_window(token_address, amount) {
+            let transfer_id = payload.transfer_id;
+            if self.pending.get_or_default(&transfer_id).is_some() {
+                self.env().revert(Error::AlreadyPending);
+            }
+            // Large releases wait out the challenge period, over-cap ones
+            // the token's delay
+            let hold = if is_large {
+                self.challenge_period.get_or_default()
+            } else {
+                limits.delay
+            };
+            let unlock_at = self.env().get_block_time() + hold;
+            self.pending.set(
+                &transfer_id,
+                Some(PendingRelease {
+                    transfer_id,
+                    source_tx_hash: payload.source_tx_hash,
+                    token: token_address,
+                    recipient,
+                    amount,
+                    fee,
+                    treasury_fee,
+                    lp_fee,
+                    operators: operators.to_vec(),
+                    nonce,
+                    target_token: payload.target_token,
+                    min_amount_out: payload.min_amount_out,
+                    unlock_at,
+                    status: ReleaseStatus::Pending,
+                }),
+            );
+            let mut ids = self.pending_ids.get_or_default();
+            ids.push(transfer_id);
+            self.pending_ids.set(ids);
+            let held = self.pending_amounts.get_or_default(&token_address);
+            self.pending_amounts.set(&token_address, held + gross_amount);
+            self.env().emit_event(ReleaseQueued {
+                transfer_id,
+                token: token_address,
+                recipient,
+                amount,
+                unlock_at,
+            });
+            return;
+        }
+
+        self.accrue_fee(token_address, fee, treasury_fee, lp_fee, operators);
+        self.deliver(
+            payload.transfer_id,
+            token_address,
+            recipient,
+            amount,
+            payload.target_token,
+            payload.min_amount_out,
+        );
+        self.record_release(token_address, amount, fee);
         self.env().emit_event(BridgeRelease {
             recipient,
             amount,
+            fee,
             nonce,
             token: token_address,
+            source_tx_hash: payload.source_tx_hash,
         });
     }
-}
\ No newline at end of file
+
+    /// Sends released funds to the recipient. With a `target_token` they are
+    /// swapped through the pair's AnchorePool first, unless there is no pool
+    /// or the quote misses `min_amount_out`: then the bridged token is
+    /// delivered as is rather than failing the release.
+    fn deliver(
+        &mut self,
+        transfer_id: [u8; 32],
+        token_address: Address,
+        recipient: Address,
+        amount: U256,
+        target_token: Option<Address>,
+        min_amount_out: U256,
+    ) {
+        if self.token_mode(token_address) == TokenMode::LockUnlock {
+            self.draw_custody(token_address, amount);
+        }
+        let target_token = match target_token {
+            Some(target_token) => target_token,
+            None => return self.pay_out(token_address, recipient, amount),
+        };
+
+        // OPTION A: Cross-Chain Swap
+        // Pools revert on slippage, and a revert can't be caught, so check the
+        // quote up front. An empty pool quotes zero.
+        let pool = self.pools.get_or_default(&sort_pair(token_address, target_token));
+        let quoted_out = match pool {
+            Some(pool) => AnchorePoolContractRef::new(self.env(), pool)
+                .get_amount_out(amount, token_address),
+            None => U256::zero(),
+        };
+        let pool = match pool {
+            Some(pool) if quoted_out > U256::zero() && quoted_out >= min_amount_out => pool,
+            _ => {
+                // OPTION B: Direct Bridge (Standard)
+                self.pay_out(token_address, recipient, amount);
+                self.env().emit_event(SwapFallback {
+                    transfer_id,
+                    token: token_address,
+                    target_token,
+                    amount,
+                    quoted_out,
+                    min_amount_out,
+                });
+                return;
+            }
+        };
+
+        // Wrapped tokens are minted to the bridge so it can fund the swap.
+        if self.token_mode(token_address) == TokenMode::MintBurn {
+            MintableTokenContractRef::new(self.env(), token_address)
+                .mint(self.env().self_address(), amount);
+        }
+        // The pool pulls the input with transfer_from, so approve it first.
+        Cep18ContractRef::new(self.env(), token_address).approve(pool, amount);
+        AnchorePoolContractRef::new(self.env(), pool)
+            .swap_exact_tokens_in(amount, token_address, min_amount_out, recipient);
+    }
+
+    fn record_release(&mut self, token: Address, amount: U256, fee: U256) {
+        let mut accounting = self.get_route_accounting(token);
+        accounting.released += amount;
+        accounting.fees += fee;
+        self.route_accounting.set(&token, accounting);
+    }
+
+    /// Releases escrowed native tokens, or mints wrapped ones
+    fn pay_out(&mut self, token: Address, recipient: Address, amount: U256) {
+        match self.token_mode(token) {
+            TokenMode::LockUnlock => {
+                Cep18ContractRef::new(self.env(), token).transfer(recipient, amount)
+            }
+            TokenMode::MintBurn => {
+                MintableTokenContractRef::new(self.env(), token).mint(recipient, amount)
+            }
+        }
+    }
+}
+
+/// The message's args with `source_chain_id` and `sender` added, so targets
+/// can tell who sent it. None if the args don't decode.
+fn message_call_args(message: &CrossChainMessage) -> Option<RuntimeArgs> {
+    let (mut args, rest) = RuntimeArgs::from_bytes(&message.args).ok()?;
+    if !rest.is_empty() {
+        return None;
+    }
+    args.insert("source_chain_id", message.source_chain_id).ok()?;
+    args.insert("sender", message.sender).ok()?;
+    Some(args)
+}
+
+/// `amount` scaled between decimals, None if that would drop a remainder
+fn convert_decimals(amount: U256, from_decimals: u8, to_decimals: u8) -> Option<U256> {
+    if to_decimals >= from_decimals {
+        return Some(amount * U256::exp10((to_decimals - from_decimals) as usize));
+    }
+    let divisor = U256::exp10((from_decimals - to_decimals) as usize);
+    if amount % divisor != U256::zero() {
+        return None;
+    }
+    Some(amount / divisor)
+}
+
+/// Pool registry key, independent of the order the pair is given in
+fn sort_pair(token_a: Address, token_b: Address) -> (Address, Address) {
+    if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    }
+}
+
+/// Replay protection key; vault nonces restart per deployment and per chain
+fn transfer_key(payload: &ReleasePayload) -> (U256, [u8; 20], U256) {
+    (payload.source_chain_id, payload.source_contract, payload.nonce)
+}
@@ -0,0 +1,91 @@
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra_modules::cep18_token::Cep18;
+use crate::access::{AccessControl, Role};
+
+// Error definitions
+#[odra::odra_error]
+pub enum Error {
+    NotBridge = 1,
+}
+
+/// CEP-18 representation of an EVM asset bridged to Casper
+/// Uses odra_modules::cep18_token::Cep18 for full CEP-18 compliance
+/// Only MINTERs, i.e. the AnchoreBridge, can mint (on release) and burn
+/// (on bridge out). The admin can move MINTER to a new bridge.
+#[odra::module]
+pub struct BridgedToken {
+    /// Internal CEP-18 implementation from odra_modules
+    token: SubModule<Cep18>,
+    /// Admin and MINTER role management
+    access: SubModule<AccessControl>,
+}
+
+#[odra::module]
+impl BridgedToken {
+    /// Initialize a wrapped token with zero supply, minted only by `bridge`
+    pub fn init(&mut self, name: String, symbol: String, decimals: u8, bridge: Address) {
+        self.token.init(symbol, name, decimals, U256::zero());
+        let admin = self.env().caller();
+        self.access.init(admin);
+        self.access.grant_role(Role::Minter, bridge);
+    }
+
+    // ============================================================
+    // BRIDGE-ONLY MINTING/BURNING
+    // ============================================================
+
+    /// Mint tokens released by the bridge
+    pub fn mint(&mut self, recipient: &Address, amount: &U256) {
+        self.assert_bridge();
+        self.token.raw_mint(recipient, amount);
+    }
+
+    /// Burn tokens leaving Casper through the bridge
+    pub fn burn(&mut self, owner: &Address, amount: &U256) {
+        self.assert_bridge();
+        self.token.raw_burn(owner, amount);
+    }
+
+    // ============================================================
+    // Access Control (Delegated to internal module)
+    // ============================================================
+
+    delegate! {
+        to self.access {
+            fn propose_admin(&mut self, new_admin: Address);
+            fn accept_admin(&mut self);
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn get_admin(&self) -> Address;
+            fn get_pending_admin(&self) -> Option<Address>;
+        }
+    }
+
+    // ============================================================
+    // CEP-18 Standard Methods (Delegated to internal module)
+    // ============================================================
+
+    delegate! {
+        to self.token {
+            fn name(&self) -> String;
+            fn symbol(&self) -> String;
+            fn decimals(&self) -> u8;
+            fn total_supply(&self) -> U256;
+            fn balance_of(&self, address: &Address) -> U256;
+            fn allowance(&self, owner: &Address, spender: &Address) -> U256;
+            fn approve(&mut self, spender: &Address, amount: &U256);
+            fn decrease_allowance(&mut self, spender: &Address, decr_by: &U256);
+            fn increase_allowance(&mut self, spender: &Address, inc_by: &U256);
+            fn transfer(&mut self, recipient: &Address, amount: &U256);
+            fn transfer_from(&mut self, owner: &Address, recipient: &Address, amount: &U256);
+        }
+    }
+
+    fn assert_bridge(&self) {
+        if !self.access.has_role(Role::Minter, self.env().caller()) {
+            self.env().revert(Error::NotBridge);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Signature verification for operator-signed bridge messages.
+//!
+//! Operators sign a canonical, domain-separated encoding of a release:
+//! `RELEASE_DOMAIN || bridge address || payload`, all bytesrepr-encoded.
+//! Binding the bridge address keeps a signature from being replayed against
+//! another bridge deployment.
+//!
+//! Operator set handovers are signed the same way, over
+//! `HANDOVER_DOMAIN || bridge address || epoch || operator set`.
+//!
+//! Batched releases are committed to by a Merkle tree of blake2b-256 hashes.
+//! Leaves are `hash(0x00 || index || payload)` and inner nodes
+//! `hash(0x01 || min(a, b) || max(a, b))`, so proofs need no left/right
+//! flags and a leaf can't be passed off as an inner node.
+//!
+//! Release recipients arrive from EVM as tagged bytes: a public key as
+//! Casper encodes it (`01` + 32-byte Ed25519 key, `02` + 33-byte secp256k1
+//! key), or `00` + a 32-byte account hash. The account address is derived
+//! here rather than by the operators.
+
+use odra::casper_types::account::AccountHash;
+use odra::casper_types::bytesrepr::{Bytes, FromBytes, ToBytes};
+use odra::casper_types::PublicKey;
+use odra::prelude::*;
+use odra::ContractEnv;
+
+use crate::bridge::{OperatorSet, ReleasePayload};
+
+/// Domain tag prepended to every signed release message
+pub const RELEASE_DOMAIN: &[u8] = b"ANCHORE_BRIDGE_RELEASE_V1";
+
+/// Canonical bytes an operator signs to approve `payload` on `bridge`
+pub fn release_message(bridge: &Address, payload: &ReleasePayload) -> Bytes {
+    let mut message = Vec::from(RELEASE_DOMAIN);
+    message.extend(bridge.to_bytes().unwrap_or_default());
+    message.extend(payload.to_bytes().unwrap_or_default());
+    Bytes::from(message)
+}
+
+/// Domain tag prepended to every signed operator set handover
+pub const HANDOVER_DOMAIN: &[u8] = b"ANCHORE_BRIDGE_HANDOVER_V1";
+
+/// Canonical bytes the current operators sign to hand `bridge` over to
+/// `operator_set` as of `epoch`
+pub fn handover_message(bridge: &Address, epoch: u32, operator_set: &OperatorSet) -> Bytes {
+    let mut message = Vec::from(HANDOVER_DOMAIN);
+    message.extend(bridge.to_bytes().unwrap_or_default());
+    message.extend(epoch.to_bytes().unwrap_or_default());
+    message.extend(operator_set.to_bytes().unwrap_or_default());
+    Bytes::from(message)
+}
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Leaf hash of `payload` at `index` of a release batch
+pub fn release_leaf(env: &ContractEnv, index: u64, payload: &ReleasePayload) -> [u8; 32] {
+    let mut leaf = Vec::from([LEAF_PREFIX]);
+    leaf.extend(index.to_bytes().unwrap_or_default());
+    leaf.extend(payload.to_bytes().unwrap_or_default());
+    env.hash(leaf)
+}
+
+/// Whether hashing `leaf` up through the sibling hashes in `proof` ends at `root`
+pub fn verify_proof(env: &ContractEnv, root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let node = proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        let mut parent = Vec::from([NODE_PREFIX]);
+        parent.extend_from_slice(&left);
+        parent.extend_from_slice(&right);
+        env.hash(parent)
+    });
+    node == *root
+}
+
+const ACCOUNT_HASH_TAG: u8 = 0x00;
+const ED25519_TAG: u8 = 0x01;
+const SECP256K1_TAG: u8 = 0x02;
+
+/// Why recipient bytes don't decode to an account
+#[derive(Debug, PartialEq, Eq)]
+pub enum RecipientError {
+    /// The first byte is none of the supported tags
+    UnknownTag,
+    /// Wrong length for the tag, or not a valid key
+    Malformed,
+}
+
+/// The account address tagged recipient bytes stand for
+pub fn recipient_address(recipient: &Bytes) -> Result<Address, RecipientError> {
+    match recipient.first() {
+        Some(&ACCOUNT_HASH_TAG) => {
+            let hash = <[u8; 32]>::try_from(&recipient[1..])
+                .map_err(|_| RecipientError::Malformed)?;
+            Ok(Address::Account(AccountHash::new(hash)))
+        }
+        Some(&ED25519_TAG) | Some(&SECP256K1_TAG) => match PublicKey::from_bytes(recipient) {
+            Ok((public_key, [])) => Ok(signer_address(&public_key)),
+            _ => Err(RecipientError::Malformed),
+        },
+        Some(_) => Err(RecipientError::UnknownTag),
+        None => Err(RecipientError::Malformed),
+    }
+}
+
+/// The account address controlled by `public_key`
+pub fn signer_address(public_key: &PublicKey) -> Address {
+    Address::Account(AccountHash::from(public_key))
+}
+
+/// Checks an Ed25519 or secp256k1 signature (bytesrepr-encoded `Signature`)
+pub fn verify(env: &ContractEnv, message: &Bytes, signature: &Bytes, public_key: &PublicKey) -> bool {
+    env.verify_signature(message, signature, public_key)
+}
@@ -1,5 +1,7 @@
 use odra::prelude::*;
 use odra::casper_types::U256;
+use crate::access::Role;
+use crate::bridge::TokenMode;
 
 #[odra::event]
 pub struct LiquidityAdded {
@@ -25,4 +27,311 @@ pub struct Swap {
     pub amount_in: U256,
     pub amount_out: U256,
     pub to: Address,
-}
\ No newline at end of file
+}
+
+#[odra::event]
+pub struct Sync {
+    pub reserve_a: U256,
+    pub reserve_b: U256,
+}
+
+#[odra::event]
+pub struct BridgeRelease {
+    pub recipient: Address,
+    pub amount: U256, // Delivered, after fees
+    pub fee: U256,
+    pub nonce: U256,
+    pub token: Address,
+    pub source_tx_hash: [u8; 32], // For matching the vault's BridgeRequested log
+}
+
+#[odra::event]
+pub struct OperatorUpdated {
+    pub operator: Address,
+    pub is_active: bool,
+}
+
+#[odra::event]
+pub struct OperatorSetProposed {
+    pub epoch: u32,
+    pub operators: Vec<Address>,
+    pub threshold: u32,
+}
+
+#[odra::event]
+pub struct EpochActivated {
+    pub epoch: u32,
+    pub operators: Vec<Address>,
+    pub threshold: u32,
+    pub approved_by_admin: bool, // Otherwise signed over by the previous set
+}
+
+#[odra::event]
+pub struct EpochGracePeriodUpdated {
+    pub grace_period: u64,
+}
+
+#[odra::event]
+pub struct TransferAttested {
+    pub transfer_id: [u8; 32],
+    pub payload_hash: [u8; 32],
+    pub operator: Address,
+    pub attestations: u32,
+}
+
+#[odra::event]
+pub struct BridgeRequested {
+    pub sender: Address,
+    pub token: Address,
+    pub amount: U256,
+    pub evm_recipient: [u8; 20],
+    pub nonce: U256,
+    pub dest_chain_id: U256,
+}
+
+#[odra::event]
+pub struct TokenModeUpdated {
+    pub token: Address,
+    pub mode: TokenMode,
+}
+
+#[odra::event]
+pub struct RouteUpdated {
+    pub source_chain_id: U256,
+    pub evm_token: [u8; 20],
+    pub token: Address,
+    pub source_decimals: u8,
+    pub local_decimals: u8,
+}
+
+#[odra::event]
+pub struct RouteRemoved {
+    pub source_chain_id: U256,
+    pub evm_token: [u8; 20],
+}
+
+#[odra::event]
+pub struct LimitsUpdated {
+    pub token: Address,
+    pub window_cap: U256,
+    pub window_duration: u64,
+    pub max_per_transfer: U256,
+    pub large_transfer_threshold: U256,
+    pub delay: u64,
+}
+
+#[odra::event]
+pub struct ChallengePeriodUpdated {
+    pub period: u64,
+}
+
+#[odra::event]
+pub struct RateLimitHit {
+    pub token: Address,
+    pub amount: U256,
+    pub window_used: U256,
+    pub window_cap: U256,
+}
+
+#[odra::event]
+pub struct ReleaseQueued {
+    pub transfer_id: [u8; 32],
+    pub token: Address,
+    pub recipient: Address,
+    pub amount: U256,
+    pub unlock_at: u64,
+}
+
+#[odra::event]
+pub struct TreasuryUpdated {
+    pub treasury: Address,
+}
+
+#[odra::event]
+pub struct FeeConfigUpdated {
+    pub source_chain_id: U256,
+    pub evm_token: [u8; 20],
+    pub fee_bps: u32,
+    pub min_fee: U256,
+    pub treasury_share_bps: u32,
+    pub lp_share_bps: u32,
+}
+
+#[odra::event]
+pub struct FeesClaimed {
+    pub claimant: Address,
+    pub token: Address,
+    pub amount: U256,
+}
+
+#[odra::event]
+pub struct BondConfigUpdated {
+    pub token: Address,
+    pub min_bond: U256,
+    pub unbonding_delay: u64,
+}
+
+#[odra::event]
+pub struct Bonded {
+    pub operator: Address,
+    pub amount: U256,
+    pub total: U256,
+}
+
+#[odra::event]
+pub struct UnbondingStarted {
+    pub operator: Address,
+    pub amount: U256,
+    pub unlock_at: u64,
+}
+
+#[odra::event]
+pub struct BondWithdrawn {
+    pub operator: Address,
+    pub amount: U256,
+}
+
+#[odra::event]
+pub struct OperatorSlashed {
+    pub operator: Address,
+    pub amount: U256,
+    pub beneficiary: Address,
+}
+
+#[odra::event]
+pub struct TransferChallenged {
+    pub transfer_id: [u8; 32],
+    pub challenger: Address,
+}
+
+#[odra::event]
+pub struct ChallengeResolved {
+    pub transfer_id: [u8; 32],
+    pub approved: bool,
+}
+
+#[odra::event]
+pub struct PoolUpdated {
+    pub token_a: Address,
+    pub token_b: Address,
+    pub pool: Address,
+}
+
+#[odra::event]
+pub struct SwapFallback {
+    pub transfer_id: [u8; 32],
+    pub token: Address,
+    pub target_token: Address,
+    pub amount: U256,
+    pub quoted_out: U256,
+    pub min_amount_out: U256,
+}
+
+#[odra::event]
+pub struct FeeRateUpdated {
+    pub fee_rate: U256,
+}
+
+#[odra::event]
+pub struct AdminTransferProposed {
+    pub admin: Address,
+    pub proposed: Address,
+}
+
+#[odra::event]
+pub struct AdminTransferred {
+    pub previous: Address,
+    pub admin: Address,
+}
+
+#[odra::event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub account: Address,
+    pub sender: Address,
+}
+
+#[odra::event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub account: Address,
+    pub sender: Address,
+}
+
+#[odra::event]
+pub struct OperatorInfoUpdated {
+    pub operator: Address,
+    pub name: String,
+    pub endpoint: String,
+}
+
+#[odra::event]
+pub struct BridgeLiquidityAdded {
+    pub provider: Address,
+    pub token: Address,
+    pub amount: U256,
+    pub shares: U256,
+}
+
+#[odra::event]
+pub struct BridgeLiquidityRemoved {
+    pub provider: Address,
+    pub token: Address,
+    pub amount: U256,
+    pub shares: U256,
+}
+
+#[odra::event]
+pub struct MessageTargetUpdated {
+    pub target: Address,
+    pub entry_point: String,
+    pub allowed: bool,
+}
+
+#[odra::event]
+pub struct MessageAttested {
+    pub source_chain_id: U256,
+    pub sender: [u8; 20],
+    pub nonce: U256,
+    pub operator: Address,
+    pub attestations: u32,
+}
+
+#[odra::event]
+pub struct MessageReceived {
+    pub source_chain_id: U256,
+    pub sender: [u8; 20],
+    pub nonce: U256,
+    pub target: Address,
+    pub entry_point: String,
+}
+
+#[odra::event]
+pub struct MessageExecuted {
+    pub source_chain_id: U256,
+    pub sender: [u8; 20],
+    pub nonce: U256,
+    pub success: bool,
+    pub attempt: u32,
+}
+
+#[odra::event]
+pub struct ReleaseBatchAttested {
+    pub root: [u8; 32],
+    pub operator: Address,
+    pub attestations: u32,
+}
+
+#[odra::event]
+pub struct ReleaseBatchPosted {
+    pub root: [u8; 32],
+    pub leaf_count: u64,
+    pub epoch: u32,
+}
+
+#[odra::event]
+pub struct ReleaseClaimed {
+    pub root: [u8; 32],
+    pub index: u64,
+    pub transfer_id: [u8; 32],
+}
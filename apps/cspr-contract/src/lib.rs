@@ -3,10 +3,23 @@
 extern crate alloc;
 
 // Declare the modules
+pub mod access;
 pub mod pool;
+pub mod bridge;
+pub mod bridged_token;
+pub mod crypto;
 pub mod events;
+pub mod liquidity;
+pub mod mock_message_target;
+pub mod mock_pool_bench;
 pub mod mock_token;
+pub mod operator_registry;
 
 // Re-export main contract structs
 pub use pool::AnchorePool;
-pub use mock_token::MockToken;
\ No newline at end of file
+pub use bridge::AnchoreBridge;
+pub use bridged_token::BridgedToken;
+pub use mock_message_target::MockMessageTarget;
+pub use mock_pool_bench::MockPoolBench;
+pub use mock_token::MockToken;
+pub use operator_registry::OperatorRegistry;
\ No newline at end of file
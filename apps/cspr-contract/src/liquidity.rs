@@ -0,0 +1,103 @@
+//! LP shares in the bridge's lock/unlock custody, the Casper counterpart of
+//! the liquidity in `AnchoreVault.sol`.
+//!
+//! Providers deposit a bridged token and get shares of the value they own
+//! in the bridge's custody: their deposits plus the LP share of release
+//! fees, less the releases paid out of them. Only that value is tracked, so
+//! escrow, operator pre-funding and anything else the bridge holds never
+//! backs shares. Releases draw on that other custody first. As in the vault,
+//! the first provider's shares equal its deposit.
+//!
+//! `AnchoreBridge` embeds `BridgeLiquidity` as a submodule and moves the
+//! tokens; this module only does the share and value accounting.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use crate::events::{BridgeLiquidityAdded, BridgeLiquidityRemoved};
+
+// Error definitions, BridgeLiquidity codes start at 31_000
+#[odra::odra_error]
+pub enum Error {
+    ZeroAmount = 31_000,
+    InsufficientShares = 31_001,
+    InsufficientLiquidity = 31_002,
+}
+
+#[odra::module]
+pub struct BridgeLiquidity {
+    total_shares: Mapping<Address, U256>,      // Per token
+    shares: Mapping<(Address, Address), U256>, // (token, provider)
+    values: Mapping<Address, U256>,            // Owned by providers, per token
+}
+
+#[odra::module]
+impl BridgeLiquidity {
+    /// Mints shares for `amount` of `token` deposited by `provider`,
+    /// returns the shares
+    pub fn deposit(&mut self, token: Address, provider: Address, amount: U256) -> U256 {
+        let (total, value) = (self.get_total_shares(token), self.get_value(token));
+        let minted = if total == U256::zero() {
+            amount
+        } else {
+            amount * total / value
+        };
+        if minted == U256::zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
+
+        self.total_shares.set(&token, total + minted);
+        self.values.set(&token, value + amount);
+        let held = self.get_shares(token, provider);
+        self.shares.set(&(token, provider), held + minted);
+        self.env().emit_event(BridgeLiquidityAdded { provider, token, amount, shares: minted });
+        minted
+    }
+
+    /// Burns `shares` of `provider`, returns their part of the value
+    pub fn withdraw(&mut self, token: Address, provider: Address, shares: U256) -> U256 {
+        if shares == U256::zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
+        let held = self.get_shares(token, provider);
+        if shares > held {
+            self.env().revert(Error::InsufficientShares);
+        }
+        let (total, value) = (self.get_total_shares(token), self.get_value(token));
+        let amount = shares * value / total;
+        if amount == U256::zero() {
+            self.env().revert(Error::InsufficientLiquidity);
+        }
+
+        self.total_shares.set(&token, total - shares);
+        self.values.set(&token, value - amount);
+        self.shares.set(&(token, provider), held - shares);
+        self.env().emit_event(BridgeLiquidityRemoved { provider, token, amount, shares });
+        amount
+    }
+
+    /// Adds `amount` of `token` earned by the providers, such as fees
+    pub fn add_value(&mut self, token: Address, amount: U256) {
+        let value = self.get_value(token);
+        self.values.set(&token, value + amount);
+    }
+
+    /// Removes `amount` of `token` paid out of the providers' value, such as
+    /// releases the rest of the custody couldn't cover
+    pub fn remove_value(&mut self, token: Address, amount: U256) {
+        let value = self.get_value(token);
+        self.values.set(&token, value - amount);
+    }
+
+    pub fn get_shares(&self, token: Address, provider: Address) -> U256 {
+        self.shares.get_or_default(&(token, provider))
+    }
+
+    pub fn get_total_shares(&self, token: Address) -> U256 {
+        self.total_shares.get_or_default(&token)
+    }
+
+    /// Value of `token` owned by providers
+    pub fn get_value(&self, token: Address) -> U256 {
+        self.values.get_or_default(&token)
+    }
+}
@@ -0,0 +1,59 @@
+use odra::prelude::*;
+use odra::casper_types::U256;
+
+// Error definitions
+#[odra::odra_error]
+pub enum Error {
+    NotBridge = 1,
+}
+
+/// Cross-chain message target for testing/demo
+/// Stores what the last accepted message carried, and declines messages
+/// while not accepting, so bridge retries can be exercised
+#[odra::module]
+pub struct MockMessageTarget {
+    bridge: Var<Address>,
+    accepting: Var<bool>,
+    last_source_chain_id: Var<U256>,
+    last_sender: Var<[u8; 20]>,
+    last_value: Var<U256>,
+}
+
+#[odra::module]
+impl MockMessageTarget {
+    pub fn init(&mut self, bridge: Address) {
+        self.bridge.set(bridge);
+        self.accepting.set(true);
+    }
+
+    pub fn set_accepting(&mut self, accepting: bool) {
+        self.accepting.set(accepting);
+    }
+
+    /// Message entry point, only callable by the bridge. Returns whether the
+    /// message was accepted.
+    pub fn store(&mut self, source_chain_id: U256, sender: [u8; 20], value: U256) -> bool {
+        if Some(self.env().caller()) != self.bridge.get() {
+            self.env().revert(Error::NotBridge);
+        }
+        if !self.accepting.get_or_default() {
+            return false;
+        }
+        self.last_source_chain_id.set(source_chain_id);
+        self.last_sender.set(sender);
+        self.last_value.set(value);
+        true
+    }
+
+    pub fn get_last_source_chain_id(&self) -> U256 {
+        self.last_source_chain_id.get_or_default()
+    }
+
+    pub fn get_last_sender(&self) -> Option<[u8; 20]> {
+        self.last_sender.get()
+    }
+
+    pub fn get_last_value(&self) -> U256 {
+        self.last_value.get_or_default()
+    }
+}
@@ -0,0 +1,101 @@
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::pool::{Cep18ContractRef, PoolConfig, PoolState};
+
+// Error definitions
+#[odra::odra_error]
+pub enum Error {
+    NotInitialized = 1,
+}
+
+/// Reserve bookkeeping of an `AnchorePool` swap, replayed under the pool's
+/// earlier designs for the gas benchmark in `tests/gas.rs`
+/// Token transfers are left out, they cost the same in every design
+#[odra::module]
+pub struct MockPoolBench {
+    // Separate `Var`s, as before the storage was packed
+    token_a: Var<Address>,
+    token_b: Var<Address>,
+    reserve_a: Var<U256>,
+    reserve_b: Var<U256>,
+    fee_rate: Var<U256>,
+    // `AnchorePool`'s packed layout
+    config: Var<PoolConfig>,
+    state: Var<PoolState>,
+}
+
+#[odra::module]
+impl MockPoolBench {
+    pub fn init(&mut self, token_a: Address, token_b: Address) {
+        self.token_a.set(token_a);
+        self.token_b.set(token_b);
+        self.reserve_a.set(U256::from(1_000_000_000));
+        self.reserve_b.set(U256::from(1_000_000_000));
+        self.fee_rate.set(U256::from(3));
+        self.config.set(PoolConfig {
+            token_a,
+            token_b,
+            fee_rate: U256::from(3),
+        });
+        self.state.set(PoolState {
+            reserve_a: U256::from(1_000_000_000),
+            reserve_b: U256::from(1_000_000_000),
+            total_supply: U256::from(1_000_000_000),
+        });
+    }
+
+    /// Before reserve tracking: reserves are re-read from both token
+    /// balances with two `balance_of` calls
+    pub fn swap_reading_balances(&mut self, amount_in: U256) {
+        self.quote(amount_in);
+        let self_address = self.env().self_address();
+        let (token_a, token_b) = self.tokens();
+        self.reserve_a
+            .set(Cep18ContractRef::new(self.env(), token_a).balance_of(self_address));
+        self.reserve_b
+            .set(Cep18ContractRef::new(self.env(), token_b).balance_of(self_address));
+    }
+
+    /// Reserves tracked from the transferred amounts, in separate `Var`s
+    pub fn swap_tracking_reserves(&mut self, amount_in: U256) {
+        let amount_out = self.quote(amount_in);
+        self.reserve_a.set(self.reserve_a.get_or_default() + amount_in);
+        self.reserve_b.set(self.reserve_b.get_or_default() - amount_out);
+    }
+
+    /// Reserves tracked from the transferred amounts, in the packed layout:
+    /// one config and one state read, one state write
+    pub fn swap_packed(&mut self, amount_in: U256) {
+        let config = self.config.get_or_revert_with(Error::NotInitialized);
+        let mut state = self.state.get_or_revert_with(Error::NotInitialized);
+        let amount_out =
+            constant_product(config.fee_rate, amount_in, state.reserve_a, state.reserve_b);
+        state.reserve_a += amount_in;
+        state.reserve_b -= amount_out;
+        self.state.set(state);
+    }
+
+    fn tokens(&self) -> (Address, Address) {
+        (
+            self.token_a.get_or_revert_with(Error::NotInitialized),
+            self.token_b.get_or_revert_with(Error::NotInitialized),
+        )
+    }
+
+    /// Output of a token_a -> token_b swap, reading what a swap reads
+    fn quote(&self, amount_in: U256) -> U256 {
+        self.tokens();
+        constant_product(
+            self.fee_rate.get_or_default(),
+            amount_in,
+            self.reserve_a.get_or_default(),
+            self.reserve_b.get_or_default(),
+        )
+    }
+}
+
+fn constant_product(fee_rate: U256, amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    let amount_in_with_fee = amount_in * (U256::from(10000) - fee_rate);
+    (amount_in_with_fee * reserve_out) / (reserve_in * U256::from(10000) + amount_in_with_fee)
+}
@@ -1,14 +1,23 @@
 use odra::prelude::*;
 use odra::casper_types::U256;
 use odra_modules::cep18_token::Cep18;
+use crate::access::{AccessControl, Role};
 
-/// Standard CEP-18 token with public minting
+// Error definitions
+#[odra::odra_error]
+pub enum Error {
+    NotMinter = 1,
+}
+
+/// Standard CEP-18 token for testing/demo
 /// Uses odra_modules::cep18_token::Cep18 for full CEP-18 compliance
-/// Anyone can mint/burn tokens (no access control for testing/demo)
+/// Accounts holding MINTER (the deployer, initially) can mint/burn tokens
 #[odra::module]
 pub struct MockToken {
     /// Internal CEP-18 implementation from odra_modules
     token: SubModule<Cep18>,
+    /// Admin and MINTER role management
+    access: SubModule<AccessControl>,
 }
 
 #[odra::module]
@@ -17,24 +26,45 @@ impl MockToken {
     pub fn init(&mut self, name: String, symbol: String, decimals: u8, initial_supply: U256) {
         // Initialize the internal CEP-18 module
         self.token.init(symbol, name, decimals, initial_supply);
+
+        let admin = self.env().caller();
+        self.access.init(admin);
+        self.access.grant_role(Role::Minter, admin);
     }
 
     // ============================================================
-    // PUBLIC MINTING/BURNING (Anyone can mint/burn for testing)
+    // MINTER-ONLY MINTING/BURNING
     // ============================================================
 
-    /// Mint tokens to any address (public, no access control)
-    /// This allows anyone to mint tokens for testing purposes
+    /// Mint tokens to any address
+    /// Grant MINTER to faucets or test accounts that need to mint
     pub fn mint(&mut self, recipient: &Address, amount: &U256) {
+        self.assert_minter();
         self.token.raw_mint(recipient, amount);
     }
 
-    /// Burn tokens from any address (public, no access control)
-    /// This allows anyone to burn tokens for testing purposes
+    /// Burn tokens from any address
     pub fn burn(&mut self, owner: &Address, amount: &U256) {
+        self.assert_minter();
         self.token.raw_burn(owner, amount);
     }
 
+    // ============================================================
+    // Access Control (Delegated to internal module)
+    // ============================================================
+
+    delegate! {
+        to self.access {
+            fn propose_admin(&mut self, new_admin: Address);
+            fn accept_admin(&mut self);
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn get_admin(&self) -> Address;
+            fn get_pending_admin(&self) -> Option<Address>;
+        }
+    }
+
     // ============================================================
     // CEP-18 Standard Methods (Delegated to internal module)
     // ============================================================
@@ -54,4 +84,10 @@ impl MockToken {
             fn transfer_from(&mut self, owner: &Address, recipient: &Address, amount: &U256);
         }
     }
-}
+
+    fn assert_minter(&self) {
+        if !self.access.has_role(Role::Minter, self.env().caller()) {
+            self.env().revert(Error::NotMinter);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,129 @@
+use odra::prelude::*;
+use odra::casper_types::PublicKey;
+use crate::access::{AccessControl, Role};
+use crate::crypto;
+use crate::events::{OperatorInfoUpdated, OperatorUpdated};
+
+// Error definitions
+#[odra::odra_error]
+pub enum Error {
+    Unauthorized = 1,
+    NotRegistered = 2,
+}
+
+/// A registered operator. Its address is the account of `public_key`, so
+/// signatures checked against the key are attributable to the operator.
+#[odra::odra_type]
+pub struct OperatorInfo {
+    pub public_key: PublicKey,
+    pub name: String,
+    pub endpoint: String, // Where the operator node can be reached
+}
+
+/// The part of `OperatorRegistry` other contracts rely on. Contracts query
+/// the registry through this interface rather than depending on the module.
+#[odra::external_contract]
+pub trait OperatorRegistryInterface {
+    fn is_operator(&self, operator: Address) -> bool;
+    fn operator_count(&self) -> u32;
+    fn get_operators(&self) -> Vec<Address>;
+    fn remove_operator(&mut self, operator: Address);
+}
+
+/// OperatorRegistry: Casper mirror of the EVM `OperatorRegistry`
+/// Implements:
+/// - set_operator / remove_operator: OPERATOR_MANAGER-managed registry of
+///   operators with their public key, name and endpoint
+/// - operator_count / get_operators: Count and enumeration of operators
+#[odra::module]
+pub struct OperatorRegistry {
+    pub access: SubModule<AccessControl>,
+    pub operators: Mapping<Address, Option<OperatorInfo>>,
+    pub operator_list: Var<Vec<Address>>, // In registration order
+}
+
+#[odra::module]
+impl OperatorRegistry {
+    pub fn init(&mut self) {
+        let admin = self.env().caller();
+        self.access.init(admin);
+        self.access.grant_role(Role::OperatorManager, admin);
+    }
+
+    // --- Access Control ---
+
+    delegate! {
+        to self.access {
+            fn propose_admin(&mut self, new_admin: Address);
+            fn accept_admin(&mut self);
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn get_admin(&self) -> Address;
+            fn get_pending_admin(&self) -> Option<Address>;
+        }
+    }
+
+    // --- Operator Management ---
+
+    /// Registers the operator controlling `public_key`, or updates its metadata
+    pub fn set_operator(&mut self, public_key: PublicKey, name: String, endpoint: String) {
+        self.assert_operator_manager();
+        let operator = crypto::signer_address(&public_key);
+        if self.operators.get_or_default(&operator).is_none() {
+            let mut list = self.operator_list.get_or_default();
+            list.push(operator);
+            self.operator_list.set(list);
+            self.env().emit_event(OperatorUpdated { operator, is_active: true });
+        }
+        self.operators.set(
+            &operator,
+            Some(OperatorInfo { public_key, name: name.clone(), endpoint: endpoint.clone() }),
+        );
+        self.env().emit_event(OperatorInfoUpdated { operator, name, endpoint });
+    }
+
+    pub fn remove_operator(&mut self, operator: Address) {
+        self.assert_operator_manager();
+        if self.operators.get_or_default(&operator).is_none() {
+            self.env().revert(Error::NotRegistered);
+        }
+        self.operators.set(&operator, None);
+        let mut list = self.operator_list.get_or_default();
+        list.retain(|registered| *registered != operator);
+        self.operator_list.set(list);
+        self.env().emit_event(OperatorUpdated { operator, is_active: false });
+    }
+
+    // --- Views ---
+
+    pub fn is_operator(&self, operator: Address) -> bool {
+        self.operators.get_or_default(&operator).is_some()
+    }
+
+    pub fn operator_count(&self) -> u32 {
+        self.operator_list.get_or_default().len() as u32
+    }
+
+    /// All operators, in registration order
+    pub fn get_operators(&self) -> Vec<Address> {
+        self.operator_list.get_or_default()
+    }
+
+    pub fn get_operator(&self, operator: Address) -> Option<OperatorInfo> {
+        self.operators.get_or_default(&operator)
+    }
+
+    /// Key the operator signs releases with
+    pub fn get_public_key(&self, operator: Address) -> Option<PublicKey> {
+        self.get_operator(operator).map(|info| info.public_key)
+    }
+
+    // --- Internal Helpers ---
+
+    fn assert_operator_manager(&self) {
+        if !self.access.has_role(Role::OperatorManager, self.env().caller()) {
+            self.env().revert(Error::Unauthorized);
+        }
+    }
+}
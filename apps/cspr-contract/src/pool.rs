@@ -1,7 +1,9 @@
 use odra::prelude::*;
+use odra::casper_types::bytesrepr::ToBytes;
 use odra::casper_types::U256;
 use odra::ContractRef;
-use crate::events::{LiquidityAdded, LiquidityRemoved, Swap};
+use crate::access::{AccessControl, Role};
+use crate::events::{FeeRateUpdated, LiquidityAdded, LiquidityRemoved, Swap, Sync};
 
 // Error definitions
 #[odra::odra_error]
@@ -12,8 +14,19 @@ pub enum Error {
     InvalidInputToken = 4,
     OutputBelowMinimum = 5,
     InputExceedsMaximum = 6,
+    LiquidityBelowMinimum = 7,
+    EmptyReserves = 8,
+    NotInitialized = 9,
+    IdenticalTokens = 10,
+    InsufficientLiquidity = 11,
+    Unauthorized = 12,
+    ZeroAddress = 13,
+    InvalidFeeRate = 14,
 }
 
+/// Highest swap fee a FEE_MANAGER can set, in basis points (10%)
+const MAX_FEE_RATE: u64 = 1_000;
+
 // Interface for External CEP-18 Token
 #[odra::external_contract]
 pub trait Cep18 {
@@ -22,36 +35,127 @@ pub trait Cep18 {
     fn balance_of(&self, address: Address) -> U256;
 }
 
+/// Pool configuration, written once at init and read once per entrypoint
+#[odra::odra_type]
+pub struct PoolConfig {
+    pub token_a: Address,
+    pub token_b: Address,
+    pub fee_rate: U256, // In basis points (3 = 0.03%)
+}
+
+/// Hot pool state, packed into a single storage slot
+#[odra::odra_type]
+pub struct PoolState {
+    pub reserve_a: U256,
+    pub reserve_b: U256,
+    pub total_supply: U256,
+}
+
+impl PoolState {
+    /// Reserves in (token_in, other) orientation; `token_in` must be a pool token
+    fn reserves_for(&self, config: &PoolConfig, token_in: Address) -> (U256, U256) {
+        if token_in == config.token_a {
+            (self.reserve_a, self.reserve_b)
+        } else {
+            (self.reserve_b, self.reserve_a)
+        }
+    }
+
+    /// Store reserves given in (token_in, other) orientation
+    fn set_reserves_for(
+        &mut self,
+        config: &PoolConfig,
+        token_in: Address,
+        reserve_in: U256,
+        reserve_out: U256,
+    ) {
+        if token_in == config.token_a {
+            self.reserve_a = reserve_in;
+            self.reserve_b = reserve_out;
+        } else {
+            self.reserve_a = reserve_out;
+            self.reserve_b = reserve_in;
+        }
+    }
+}
+
 /// AnchorePool: Uniswap V2-like constant product AMM
 /// Implements:
 /// - add_liquidity: Deposit token pair, receive LP tokens
 /// - remove_liquidity: Burn LP tokens, receive token pair
 /// - swap_exact_tokens_in: Swap fixed input for variable output
 /// - swap_tokens_for_exact: Swap variable input for fixed output
+/// - zap_in / zap_out: Single-sided liquidity provision and withdrawal
+/// - set_fee_rate: FEE_MANAGER-set swap fee, under a shared access control
 /// - Price oracle functions
+///
+/// Storage: every entrypoint reads `config` and `state` once and writes
+/// `state` once, plus the LP balances it touches.
 #[odra::module]
 pub struct AnchorePool {
-    pub token_a: Var<Address>,
-    pub token_b: Var<Address>,
-    pub reserve_a: Var<U256>,
-    pub reserve_b: Var<U256>,
-    pub total_supply: Var<U256>,
+    pub access: SubModule<AccessControl>,
+    pub config: Var<PoolConfig>,
+    pub state: Var<PoolState>,
     pub balances: Mapping<Address, U256>,
-    pub fee_rate: Var<U256>, // In basis points (3 = 0.3%)
-    pub accumulated_fees_a: Var<U256>,
-    pub accumulated_fees_b: Var<U256>,
 }
 
 #[odra::module]
 impl AnchorePool {
-    #[odra(init)]
+    /// Tokens are stored in canonical (sorted) order, so a pair maps to a
+    /// single pool regardless of argument order. Use `get_tokens` to read it back.
     pub fn init(&mut self, token_a: Address, token_b: Address) {
-        self.token_a.set(token_a);
-        self.token_b.set(token_b);
-        self.reserve_a.set(U256::zero());
-        self.reserve_b.set(U256::zero());
-        self.total_supply.set(U256::zero());
-        self.fee_rate.set(U256::from(3)); // 0.3% fee
+        if is_zero_address(&token_a) || is_zero_address(&token_b) {
+            self.env().revert(Error::ZeroAddress);
+        }
+        if token_a == token_b {
+            self.env().revert(Error::IdenticalTokens);
+        }
+
+        let admin = self.env().caller();
+        self.access.init(admin);
+        self.access.grant_role(Role::FeeManager, admin);
+
+        let (token_a, token_b) = sort_tokens(token_a, token_b);
+        self.config.set(PoolConfig {
+            token_a,
+            token_b,
+            fee_rate: U256::from(3), // 0.03% fee
+        });
+        self.state.set(PoolState {
+            reserve_a: U256::zero(),
+            reserve_b: U256::zero(),
+            total_supply: U256::zero(),
+        });
+    }
+
+    // ============================================================
+    // ACCESS CONTROL
+    // ============================================================
+
+    delegate! {
+        to self.access {
+            fn propose_admin(&mut self, new_admin: Address);
+            fn accept_admin(&mut self);
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn get_admin(&self) -> Address;
+            fn get_pending_admin(&self) -> Option<Address>;
+        }
+    }
+
+    /// Set the swap fee in basis points, at most `MAX_FEE_RATE`
+    pub fn set_fee_rate(&mut self, fee_rate: U256) {
+        if !self.access.has_role(Role::FeeManager, self.env().caller()) {
+            self.env().revert(Error::Unauthorized);
+        }
+        if fee_rate > U256::from(MAX_FEE_RATE) {
+            self.env().revert(Error::InvalidFeeRate);
+        }
+        let mut config = self.config();
+        config.fee_rate = fee_rate;
+        self.config.set(config);
+        self.env().emit_event(FeeRateUpdated { fee_rate });
     }
 
     // ============================================================
@@ -63,8 +167,8 @@ impl AnchorePool {
     /// - Receives LP tokens representing their share
     pub fn add_liquidity(&mut self, amount_a: U256, amount_b: U256) {
         let caller = self.env().caller();
-        let t_a = self.token_a.get().unwrap();
-        let t_b = self.token_b.get().unwrap();
+        let config = self.config();
+        let mut state = self.state();
 
         // Require non-zero amounts
         if amount_a == U256::zero() || amount_b == U256::zero() {
@@ -72,20 +176,25 @@ impl AnchorePool {
         }
 
         // Transfer tokens to pool
-        Cep18ContractRef::new(self.env(), t_a)
+        Cep18ContractRef::new(self.env(), config.token_a)
             .transfer_from(caller, self.env().self_address(), amount_a);
-        Cep18ContractRef::new(self.env(), t_b)
+        Cep18ContractRef::new(self.env(), config.token_b)
             .transfer_from(caller, self.env().self_address(), amount_b);
 
         // Calculate liquidity tokens to mint
-        let liquidity = self.calculate_liquidity_to_mint(amount_a, amount_b);
+        let liquidity = self.calculate_liquidity_to_mint(&state, amount_a, amount_b);
+        if liquidity == U256::zero() {
+            self.env().revert(Error::InsufficientLiquidity);
+        }
 
         // Mint LP tokens to caller
         self.balances.set(&caller, self.balances.get_or_default(&caller) + liquidity);
-        self.total_supply.set(self.total_supply.get_or_default() + liquidity);
 
-        // Update reserves
-        self.sync_reserves();
+        // Update supply and reserves from the transferred amounts
+        state.total_supply += liquidity;
+        state.reserve_a += amount_a;
+        state.reserve_b += amount_b;
+        self.state.set(state);
 
         self.env().emit_event(LiquidityAdded {
             provider: caller,
@@ -100,8 +209,8 @@ impl AnchorePool {
     /// - Receives proportional token pair
     pub fn remove_liquidity(&mut self, liquidity_tokens: U256) {
         let caller = self.env().caller();
-        let t_a = self.token_a.get().unwrap();
-        let t_b = self.token_b.get().unwrap();
+        let config = self.config();
+        let mut state = self.state();
 
         // Require positive liquidity
         if liquidity_tokens == U256::zero() {
@@ -115,23 +224,24 @@ impl AnchorePool {
         }
 
         // Calculate amounts to return
-        let total_supply = self.total_supply.get_or_default();
-        let reserve_a = self.reserve_a.get_or_default();
-        let reserve_b = self.reserve_b.get_or_default();
-
-        let amount_a = (liquidity_tokens * reserve_a) / total_supply;
-        let amount_b = (liquidity_tokens * reserve_b) / total_supply;
+        let amount_a = (liquidity_tokens * state.reserve_a) / state.total_supply;
+        let amount_b = (liquidity_tokens * state.reserve_b) / state.total_supply;
+        if amount_a == U256::zero() || amount_b == U256::zero() {
+            self.env().revert(Error::InsufficientLiquidity);
+        }
 
         // Burn LP tokens
         self.balances.set(&caller, caller_balance - liquidity_tokens);
-        self.total_supply.set(total_supply - liquidity_tokens);
 
-        // Transfer tokens to caller
-        Cep18ContractRef::new(self.env(), t_a).transfer(caller, amount_a);
-        Cep18ContractRef::new(self.env(), t_b).transfer(caller, amount_b);
+        // Update supply and reserves from the transferred amounts
+        state.total_supply -= liquidity_tokens;
+        state.reserve_a -= amount_a;
+        state.reserve_b -= amount_b;
+        self.state.set(state);
 
-        // Update reserves
-        self.sync_reserves();
+        // Transfer tokens to caller
+        Cep18ContractRef::new(self.env(), config.token_a).transfer(caller, amount_a);
+        Cep18ContractRef::new(self.env(), config.token_b).transfer(caller, amount_b);
 
         self.env().emit_event(LiquidityRemoved {
             provider: caller,
@@ -155,23 +265,27 @@ impl AnchorePool {
         min_amount_out: U256,
         to: Address,
     ) {
-        let t_a = self.token_a.get().unwrap();
-        let t_b = self.token_b.get().unwrap();
+        let config = self.config();
+        let mut state = self.state();
 
-        // Validate token_in
-        if token_in != t_a && token_in != t_b {
-            self.env().revert(Error::InvalidInputToken);
+        if amount_in == U256::zero() {
+            self.env().revert(Error::ZeroAmount);
         }
 
-        // Determine output token and reserves
-        let (token_out, reserve_in, reserve_out) = if token_in == t_a {
-            (t_b, self.reserve_a.get_or_default(), self.reserve_b.get_or_default())
-        } else {
-            (t_a, self.reserve_b.get_or_default(), self.reserve_a.get_or_default())
-        };
+        // Validate token_in and determine output token and reserves
+        let token_out = self.other_token(&config, token_in);
+        let (reserve_in, reserve_out) = state.reserves_for(&config, token_in);
+
+        if reserve_in == U256::zero() || reserve_out == U256::zero() {
+            self.env().revert(Error::EmptyReserves);
+        }
 
         // Calculate output amount (with 0.3% fee)
-        let amount_out = self.calculate_output_amount(amount_in, reserve_in, reserve_out);
+        let amount_out =
+            self.calculate_output_amount(config.fee_rate, amount_in, reserve_in, reserve_out);
+        if amount_out == U256::zero() {
+            self.env().revert(Error::InsufficientLiquidity);
+        }
 
         // Check minimum output
         if amount_out < min_amount_out {
@@ -185,8 +299,9 @@ impl AnchorePool {
         // Transfer output to recipient
         Cep18ContractRef::new(self.env(), token_out).transfer(to, amount_out);
 
-        // Update reserves
-        self.sync_reserves();
+        // Update reserves from the transferred amounts
+        state.set_reserves_for(&config, token_in, reserve_in + amount_in, reserve_out - amount_out);
+        self.state.set(state);
 
         self.env().emit_event(Swap {
             sender: self.env().caller(),
@@ -208,23 +323,27 @@ impl AnchorePool {
         max_amount_in: U256,
         to: Address,
     ) {
-        let t_a = self.token_a.get().unwrap();
-        let t_b = self.token_b.get().unwrap();
+        let config = self.config();
+        let mut state = self.state();
 
-        // Validate token_in
-        if token_in != t_a && token_in != t_b {
-            self.env().revert(Error::InvalidInputToken);
+        if amount_out == U256::zero() {
+            self.env().revert(Error::ZeroAmount);
         }
 
-        // Determine output token and reserves
-        let (token_out, reserve_in, reserve_out) = if token_in == t_a {
-            (t_b, self.reserve_a.get_or_default(), self.reserve_b.get_or_default())
-        } else {
-            (t_a, self.reserve_b.get_or_default(), self.reserve_a.get_or_default())
-        };
+        // Validate token_in and determine output token and reserves
+        let token_out = self.other_token(&config, token_in);
+        let (reserve_in, reserve_out) = state.reserves_for(&config, token_in);
+
+        if reserve_in == U256::zero() || reserve_out == U256::zero() {
+            self.env().revert(Error::EmptyReserves);
+        }
+        if amount_out >= reserve_out {
+            self.env().revert(Error::InsufficientLiquidity);
+        }
 
         // Calculate required input amount
-        let amount_in = self.calculate_input_for_output(amount_out, reserve_in, reserve_out);
+        let amount_in =
+            self.calculate_input_for_output(config.fee_rate, amount_out, reserve_in, reserve_out);
 
         // Check maximum input
         if amount_in > max_amount_in {
@@ -238,8 +357,9 @@ impl AnchorePool {
         // Transfer output to recipient
         Cep18ContractRef::new(self.env(), token_out).transfer(to, amount_out);
 
-        // Update reserves
-        self.sync_reserves();
+        // Update reserves from the transferred amounts
+        state.set_reserves_for(&config, token_in, reserve_in + amount_in, reserve_out - amount_out);
+        self.state.set(state);
 
         self.env().emit_event(Swap {
             sender: self.env().caller(),
@@ -251,21 +371,196 @@ impl AnchorePool {
         });
     }
 
+    // ============================================================
+    // ZAP FUNCTIONS
+    // ============================================================
+
+    /// Provide liquidity using a single token
+    /// - Swaps the fee-adjusted optimal fraction of amount_in into the other token
+    /// - Deposits both sides and mints LP tokens to `to`
+    /// - Refunds any leftover of either token to the caller
+    pub fn zap_in(&mut self, token_in: Address, amount_in: U256, min_lp_out: U256, to: Address) {
+        let caller = self.env().caller();
+        let config = self.config();
+        let mut state = self.state();
+
+        if amount_in == U256::zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
+
+        let token_out = self.other_token(&config, token_in);
+        let (reserve_in, reserve_out) = state.reserves_for(&config, token_in);
+        if reserve_in == U256::zero() || reserve_out == U256::zero() {
+            self.env().revert(Error::EmptyReserves);
+        }
+
+        // Pull the full input amount once
+        Cep18ContractRef::new(self.env(), token_in)
+            .transfer_from(caller, self.env().self_address(), amount_in);
+
+        // Swap part of the input so the remainder matches the post-swap pool ratio
+        let swap_amount = self.calculate_zap_swap_amount(config.fee_rate, amount_in, reserve_in);
+        let swapped_out =
+            self.calculate_output_amount(config.fee_rate, swap_amount, reserve_in, reserve_out);
+        let reserve_in = reserve_in + swap_amount;
+        let reserve_out = reserve_out - swapped_out;
+        state.set_reserves_for(&config, token_in, reserve_in, reserve_out);
+
+        // Deposit at the post-swap ratio, whatever doesn't fit is refunded
+        let desired_in = amount_in - swap_amount;
+        let optimal_out = (desired_in * reserve_out) / reserve_in;
+        let (deposit_in, deposit_out) = if optimal_out <= swapped_out {
+            (desired_in, optimal_out)
+        } else {
+            ((swapped_out * reserve_in) / reserve_out, swapped_out)
+        };
+
+        let (deposit_a, deposit_b) = if token_in == config.token_a {
+            (deposit_in, deposit_out)
+        } else {
+            (deposit_out, deposit_in)
+        };
+
+        let liquidity = self.calculate_liquidity_to_mint(&state, deposit_a, deposit_b);
+        if liquidity == U256::zero() || liquidity < min_lp_out {
+            self.env().revert(Error::LiquidityBelowMinimum);
+        }
+
+        // Mint LP tokens to recipient
+        self.balances.set(&to, self.balances.get_or_default(&to) + liquidity);
+
+        // Update supply and reserves from the deposited amounts
+        state.total_supply += liquidity;
+        state.reserve_a += deposit_a;
+        state.reserve_b += deposit_b;
+        self.state.set(state);
+
+        // Refund dust
+        if desired_in > deposit_in {
+            Cep18ContractRef::new(self.env(), token_in).transfer(caller, desired_in - deposit_in);
+        }
+        if swapped_out > deposit_out {
+            Cep18ContractRef::new(self.env(), token_out).transfer(caller, swapped_out - deposit_out);
+        }
+
+        self.env().emit_event(Swap {
+            sender: caller,
+            token_in,
+            token_out,
+            amount_in: swap_amount,
+            amount_out: swapped_out,
+            to: self.env().self_address(),
+        });
+        self.env().emit_event(LiquidityAdded {
+            provider: to,
+            amount_a: deposit_a,
+            amount_b: deposit_b,
+            liquidity,
+        });
+    }
+
+    /// Remove liquidity into a single token
+    /// - Burns LP tokens for the proportional token pair
+    /// - Swaps the other side into token_out against the remaining reserves
+    /// - Sends the combined amount to the caller
+    pub fn zap_out(&mut self, lp_amount: U256, token_out: Address, min_out: U256) {
+        let caller = self.env().caller();
+        let config = self.config();
+        let mut state = self.state();
+
+        if lp_amount == U256::zero() {
+            self.env().revert(Error::ZeroLiquidity);
+        }
+
+        // Validates token_out; the "input" side of the internal swap is the other token
+        let token_in = self.other_token(&config, token_out);
+        let (reserve_out, reserve_in) = state.reserves_for(&config, token_out);
+
+        let caller_balance = self.balances.get_or_default(&caller);
+        if caller_balance < lp_amount {
+            self.env().revert(Error::InsufficientLpBalance);
+        }
+
+        let amount_out_side = (lp_amount * reserve_out) / state.total_supply;
+        let amount_in_side = (lp_amount * reserve_in) / state.total_supply;
+
+        // Burn LP tokens
+        self.balances.set(&caller, caller_balance - lp_amount);
+
+        // Swap the withdrawn other side against the post-withdrawal reserves
+        let swapped_out = self.calculate_output_amount(
+            config.fee_rate,
+            amount_in_side,
+            reserve_in - amount_in_side,
+            reserve_out - amount_out_side,
+        );
+        let amount_out = amount_out_side + swapped_out;
+        if amount_out < min_out {
+            self.env().revert(Error::OutputBelowMinimum);
+        }
+
+        // Update supply and reserves: the withdrawn other side was swapped straight back in
+        state.total_supply -= lp_amount;
+        state.set_reserves_for(&config, token_out, reserve_out - amount_out, reserve_in);
+        self.state.set(state);
+
+        Cep18ContractRef::new(self.env(), token_out).transfer(caller, amount_out);
+
+        let (amount_a, amount_b) = if token_out == config.token_a {
+            (amount_out_side, amount_in_side)
+        } else {
+            (amount_in_side, amount_out_side)
+        };
+        self.env().emit_event(LiquidityRemoved {
+            provider: caller,
+            amount_a,
+            amount_b,
+            liquidity: lp_amount,
+        });
+        self.env().emit_event(Swap {
+            sender: caller,
+            token_in,
+            token_out,
+            amount_in: amount_in_side,
+            amount_out: swapped_out,
+            to: caller,
+        });
+    }
+
+    // ============================================================
+    // RESERVE RECONCILIATION
+    // ============================================================
+
+    /// Reset reserves to the pool's actual token balances
+    /// - Entrypoints track reserves from transfer amounts, so tokens sent
+    ///   directly to the pool are only picked up here
+    pub fn sync(&mut self) {
+        let config = self.config();
+        let mut state = self.state();
+        let self_address = self.env().self_address();
+
+        let reserve_a = Cep18ContractRef::new(self.env(), config.token_a).balance_of(self_address);
+        let reserve_b = Cep18ContractRef::new(self.env(), config.token_b).balance_of(self_address);
+        state.reserve_a = reserve_a;
+        state.reserve_b = reserve_b;
+        self.state.set(state);
+
+        self.env().emit_event(Sync { reserve_a, reserve_b });
+    }
+
     // ============================================================
     // VIEW FUNCTIONS
     // ============================================================
 
     /// Get current reserves of both tokens
     pub fn get_reserves(&self) -> (U256, U256) {
-        (
-            self.reserve_a.get_or_default(),
-            self.reserve_b.get_or_default(),
-        )
+        let state = self.state();
+        (state.reserve_a, state.reserve_b)
     }
 
     /// Get total LP token supply
     pub fn get_lp_token_supply(&self) -> U256 {
-        self.total_supply.get_or_default()
+        self.state().total_supply
     }
 
     /// Get LP token balance of an address
@@ -273,64 +568,86 @@ impl AnchorePool {
         self.balances.get_or_default(&address)
     }
 
-    /// Get current price of token_a in terms of token_b
-    /// Price = reserve_b / reserve_a
+    /// Get the pool tokens in canonical order (token_a < token_b)
+    pub fn get_tokens(&self) -> (Address, Address) {
+        let config = self.config();
+        (config.token_a, config.token_b)
+    }
+
+    /// Get the swap fee in basis points
+    pub fn get_fee_rate(&self) -> U256 {
+        self.config().fee_rate
+    }
+
+    /// Get current price of `token` in terms of the other pool token
+    /// Price = reserve_other / reserve_token (scaled by 1e18)
     pub fn get_price(&self, token: Address) -> U256 {
-        let t_a = self.token_a.get().unwrap();
-        let reserve_a = self.reserve_a.get_or_default();
-        let reserve_b = self.reserve_b.get_or_default();
+        let config = self.config();
+        // Validate token
+        self.other_token(&config, token);
+        let (reserve_token, reserve_other) = self.state().reserves_for(&config, token);
 
-        if reserve_a == U256::zero() || reserve_b == U256::zero() {
+        if reserve_token == U256::zero() || reserve_other == U256::zero() {
             return U256::zero();
         }
 
-        if token == t_a {
-            (reserve_b * U256::from(1_000_000_000_000_000_000u128)) / reserve_a
-        } else {
-            (reserve_a * U256::from(1_000_000_000_000_000_000u128)) / reserve_b
-        }
+        (reserve_other * U256::from(1_000_000_000_000_000_000u128)) / reserve_token
     }
 
     /// Simulate output amount for given input
     pub fn get_amount_out(&self, amount_in: U256, token_in: Address) -> U256 {
-        let t_a = self.token_a.get().unwrap();
-        let (reserve_in, reserve_out) = if token_in == t_a {
-            (self.reserve_a.get_or_default(), self.reserve_b.get_or_default())
-        } else {
-            (self.reserve_b.get_or_default(), self.reserve_a.get_or_default())
-        };
-
-        self.calculate_output_amount(amount_in, reserve_in, reserve_out)
+        let config = self.config();
+        // Validate token_in
+        self.other_token(&config, token_in);
+        let (reserve_in, reserve_out) = self.state().reserves_for(&config, token_in);
+        self.calculate_output_amount(config.fee_rate, amount_in, reserve_in, reserve_out)
     }
 
     /// Simulate input amount needed for desired output
     pub fn get_amount_in(&self, amount_out: U256, token_in: Address) -> U256 {
-        let t_a = self.token_a.get().unwrap();
-        let (reserve_in, reserve_out) = if token_in == t_a {
-            (self.reserve_a.get_or_default(), self.reserve_b.get_or_default())
-        } else {
-            (self.reserve_b.get_or_default(), self.reserve_a.get_or_default())
-        };
+        let config = self.config();
+        // Validate token_in
+        self.other_token(&config, token_in);
+        let (reserve_in, reserve_out) = self.state().reserves_for(&config, token_in);
+        self.calculate_input_for_output(config.fee_rate, amount_out, reserve_in, reserve_out)
+    }
 
-        self.calculate_input_for_output(amount_out, reserve_in, reserve_out)
+    /// Simulate how much of a single-sided `zap_in` deposit is swapped
+    pub fn get_zap_swap_amount(&self, amount_in: U256, token_in: Address) -> U256 {
+        let config = self.config();
+        // Validate token_in
+        self.other_token(&config, token_in);
+        let (reserve_in, _) = self.state().reserves_for(&config, token_in);
+        self.calculate_zap_swap_amount(config.fee_rate, amount_in, reserve_in)
     }
 
     // ============================================================
     // INTERNAL HELPER FUNCTIONS
     // ============================================================
 
-    fn sync_reserves(&mut self) {
-        let t_a = self.token_a.get().unwrap();
-        let t_b = self.token_b.get().unwrap();
-        self.reserve_a.set(Cep18ContractRef::new(self.env(), t_a).balance_of(self.env().self_address()));
-        self.reserve_b.set(Cep18ContractRef::new(self.env(), t_b).balance_of(self.env().self_address()));
+    fn config(&self) -> PoolConfig {
+        self.config.get_or_revert_with(Error::NotInitialized)
+    }
+
+    fn state(&self) -> PoolState {
+        self.state.get_or_revert_with(Error::NotInitialized)
+    }
+
+    /// Resolve the counterpart of a pool token, reverting for tokens outside the pair
+    fn other_token(&self, config: &PoolConfig, token_in: Address) -> Address {
+        if token_in == config.token_a {
+            config.token_b
+        } else if token_in == config.token_b {
+            config.token_a
+        } else {
+            self.env().revert(Error::InvalidInputToken)
+        }
     }
 
-    /// Calculate LP tokens to mint for given deposit amounts
-    fn calculate_liquidity_to_mint(&self, amount_a: U256, amount_b: U256) -> U256 {
-        let total_supply = self.total_supply.get_or_default();
-        let reserve_a = self.reserve_a.get_or_default();
-        let reserve_b = self.reserve_b.get_or_default();
+    /// Calculate LP tokens to mint for given deposit amounts against the given state
+    fn calculate_liquidity_to_mint(&self, state: &PoolState, amount_a: U256, amount_b: U256) -> U256 {
+        let total_supply = state.total_supply;
+        let (reserve_a, reserve_b) = (state.reserve_a, state.reserve_b);
 
         if total_supply == U256::zero() {
             // Initial liquidity: sqrt(amount_a * amount_b)
@@ -346,22 +663,47 @@ impl AnchorePool {
 
     /// Calculate output amount for given input using constant product formula
     /// Formula: amount_out = (amount_in * 997 * reserve_out) / (reserve_in * 1000 + amount_in * 997)
-    fn calculate_output_amount(&self, amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    fn calculate_output_amount(
+        &self,
+        fee_rate: U256,
+        amount_in: U256,
+        reserve_in: U256,
+        reserve_out: U256,
+    ) -> U256 {
         if amount_in == U256::zero() || reserve_in == U256::zero() || reserve_out == U256::zero() {
             return U256::zero();
         }
 
-        let fee_rate = self.fee_rate.get_or_default();
-        let amount_in_with_fee = amount_in * (U256::from(10000) - fee_rate) / U256::from(10000);
+        let amount_in_with_fee = amount_in * (U256::from(10000) - fee_rate);
         let numerator = amount_in_with_fee * reserve_out;
         let denominator = reserve_in * U256::from(10000) + amount_in_with_fee;
         
         numerator / denominator
     }
 
+    /// Calculate how much of a single-sided deposit to swap so the remainder
+    /// matches the post-swap reserve ratio, accounting for the swap fee
+    /// Formula: s = (sqrt(r^2 * (F + g)^2 + 4 * a * r * g * F) - r * (F + g)) / (2 * g)
+    /// where r = reserve_in, a = amount_in, F = 10000, g = F - fee_rate
+    fn calculate_zap_swap_amount(&self, fee_rate: U256, amount_in: U256, reserve_in: U256) -> U256 {
+        let fee_denominator = U256::from(10000);
+        let fee_factor = fee_denominator - fee_rate;
+        let sum = fee_denominator + fee_factor;
+
+        let discriminant = reserve_in * reserve_in * sum * sum
+            + U256::from(4) * amount_in * reserve_in * fee_factor * fee_denominator;
+        (self.isqrt(discriminant) - reserve_in * sum) / (U256::from(2) * fee_factor)
+    }
+
     /// Calculate input amount needed for desired output
     /// Formula (inverted): amount_in = (reserve_in * amount_out * 1000) / (reserve_out * 997 - amount_out * 997)
-    fn calculate_input_for_output(&self, amount_out: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    fn calculate_input_for_output(
+        &self,
+        fee_rate: U256,
+        amount_out: U256,
+        reserve_in: U256,
+        reserve_out: U256,
+    ) -> U256 {
         if amount_out == U256::zero() || reserve_in == U256::zero() || reserve_out == U256::zero() {
             return U256::zero();
         }
@@ -371,7 +713,6 @@ impl AnchorePool {
             return U256::MAX;
         }
 
-        let fee_rate = self.fee_rate.get_or_default();
         let fee_factor = U256::from(10000) - fee_rate;
         let numerator = reserve_in * amount_out * U256::from(10000);
         let denominator = (reserve_out - amount_out) * fee_factor;
@@ -399,3 +740,20 @@ impl AnchorePool {
         x
     }
 }
+
+/// Order a token pair canonically so each pair has a single representation
+fn sort_tokens(token_a: Address, token_b: Address) -> (Address, Address) {
+    if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    }
+}
+
+/// An address whose hash bytes are all zero (account or contract)
+fn is_zero_address(address: &Address) -> bool {
+    address
+        .to_bytes()
+        .map(|bytes| bytes.iter().skip(1).all(|byte| *byte == 0))
+        .unwrap_or(true)
+}
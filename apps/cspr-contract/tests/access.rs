@@ -0,0 +1,169 @@
+//! Integration tests for the shared `AccessControl` module, through the
+//! contracts that embed it.
+
+mod common;
+
+use common::{bridge_setup, deploy_token, seeded_pool};
+use cspr_contract::access::{Error as AccessError, Role};
+use cspr_contract::bridge::Error as BridgeError;
+use cspr_contract::bridged_token::{BridgedToken, BridgedTokenInitArgs, Error as BridgedTokenError};
+use cspr_contract::events::{AdminTransferred, FeeRateUpdated, RoleGranted, RoleRevoked};
+use cspr_contract::mock_token::Error as MockTokenError;
+use cspr_contract::operator_registry::Error as RegistryError;
+use cspr_contract::pool::Error as PoolError;
+use odra::casper_types::U256;
+use odra::host::Deployer;
+use odra::prelude::*;
+
+#[test]
+fn admin_is_handed_over_in_two_steps() {
+    let mut s = bridge_setup();
+    let (admin, successor) = (s.admin, s.env.get_account(4));
+
+    s.env.set_caller(successor);
+    assert_eq!(
+        s.bridge.try_propose_admin(successor),
+        Err(AccessError::NotAdmin.into())
+    );
+
+    s.env.set_caller(admin);
+    s.bridge.propose_admin(successor);
+    assert_eq!(s.bridge.get_pending_admin(), Some(successor));
+    // Proposing doesn't hand anything over yet
+    assert_eq!(s.bridge.get_admin(), admin);
+
+    s.env.set_caller(s.user);
+    assert_eq!(s.bridge.try_accept_admin(), Err(AccessError::NotPendingAdmin.into()));
+
+    s.env.set_caller(successor);
+    s.bridge.accept_admin();
+    assert_eq!(s.bridge.get_admin(), successor);
+    assert_eq!(s.bridge.get_pending_admin(), None);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        AdminTransferred { previous: admin, admin: successor }
+    ));
+
+    s.env.set_caller(admin);
+    assert_eq!(
+        s.bridge.try_set_epoch_grace_period(0),
+        Err(BridgeError::Unauthorized.into())
+    );
+}
+
+#[test]
+fn roles_are_granted_and_revoked_by_the_admin() {
+    let mut s = bridge_setup();
+    let (admin, manager, candidate) = (s.admin, s.env.get_account(4), s.env.get_account(5));
+
+    assert_eq!(
+        s.registry.try_grant_role(Role::Admin, manager),
+        Err(AccessError::AdminRoleNotGrantable.into())
+    );
+
+    s.registry.grant_role(Role::OperatorManager, manager);
+    assert!(s.registry.has_role(Role::OperatorManager, manager));
+    assert!(s.env.emitted_event(
+        &s.registry.address(),
+        RoleGranted { role: Role::OperatorManager, account: manager, sender: admin }
+    ));
+
+    // OPERATOR_MANAGER registers operators but can't hand out roles
+    s.env.set_caller(manager);
+    s.add_operator(candidate);
+    assert!(s.registry.is_operator(candidate));
+    assert_eq!(
+        s.registry.try_grant_role(Role::OperatorManager, candidate),
+        Err(AccessError::NotAdmin.into())
+    );
+
+    s.env.set_caller(admin);
+    s.registry.revoke_role(Role::OperatorManager, manager);
+    assert!(s.env.emitted_event(
+        &s.registry.address(),
+        RoleRevoked { role: Role::OperatorManager, account: manager, sender: admin }
+    ));
+    s.env.set_caller(manager);
+    assert_eq!(
+        s.registry.try_remove_operator(candidate),
+        Err(RegistryError::Unauthorized.into())
+    );
+}
+
+#[test]
+fn only_minters_mint_mock_tokens() {
+    let env = odra_test::env();
+    let mut token = deploy_token(&env, "TKA");
+    let (deployer, faucet) = (env.get_account(0), env.get_account(1));
+    assert!(token.has_role(Role::Minter, deployer));
+
+    env.set_caller(faucet);
+    assert_eq!(
+        token.try_mint(&faucet, &U256::from(1_000)),
+        Err(MockTokenError::NotMinter.into())
+    );
+    assert_eq!(
+        token.try_burn(&deployer, &U256::one()),
+        Err(MockTokenError::NotMinter.into())
+    );
+
+    env.set_caller(deployer);
+    token.grant_role(Role::Minter, faucet);
+    env.set_caller(faucet);
+    token.mint(&faucet, &U256::from(1_000));
+    assert_eq!(token.balance_of(&faucet), U256::from(1_000));
+}
+
+#[test]
+fn bridged_token_minter_moves_with_the_bridge() {
+    let env = odra_test::env();
+    let (old_bridge, new_bridge, user) = (env.get_account(4), env.get_account(5), env.get_account(3));
+    let mut wrapped = BridgedToken::deploy(
+        &env,
+        BridgedTokenInitArgs {
+            name: "Bridged USDC".to_string(),
+            symbol: "bUSDC".to_string(),
+            decimals: 6,
+            bridge: old_bridge,
+        },
+    );
+    assert!(wrapped.has_role(Role::Minter, old_bridge));
+
+    wrapped.grant_role(Role::Minter, new_bridge);
+    wrapped.revoke_role(Role::Minter, old_bridge);
+
+    env.set_caller(old_bridge);
+    assert_eq!(
+        wrapped.try_mint(&user, &U256::one()),
+        Err(BridgedTokenError::NotBridge.into())
+    );
+    env.set_caller(new_bridge);
+    wrapped.mint(&user, &U256::one());
+    assert_eq!(wrapped.balance_of(&user), U256::one());
+}
+
+#[test]
+fn fee_managers_set_the_pool_fee() {
+    let mut s = seeded_pool();
+    let manager = s.env.get_account(1);
+
+    s.env.set_caller(manager);
+    assert_eq!(
+        s.pool.try_set_fee_rate(U256::from(5)),
+        Err(PoolError::Unauthorized.into())
+    );
+
+    s.env.set_caller(s.env.get_account(0));
+    s.pool.grant_role(Role::FeeManager, manager);
+    s.env.set_caller(manager);
+    assert_eq!(
+        s.pool.try_set_fee_rate(U256::from(1_001)),
+        Err(PoolError::InvalidFeeRate.into())
+    );
+    s.pool.set_fee_rate(U256::from(5));
+    assert_eq!(s.pool.get_fee_rate(), U256::from(5));
+    assert!(s.env.emitted_event(
+        &s.pool.address(),
+        FeeRateUpdated { fee_rate: U256::from(5) }
+    ));
+}
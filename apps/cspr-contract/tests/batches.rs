@@ -0,0 +1,169 @@
+//! Integration tests for Merkle-batched releases through `AnchoreBridge`.
+
+mod common;
+
+use blake2::digest::{Update, VariableOutput};
+use blake2::Blake2bVar;
+use common::{bridge_setup, BridgeSetup, EVM_VAULT, SOURCE_CHAIN_ID};
+use cspr_contract::bridge::{Error, ReleasePayload};
+use cspr_contract::events::{ReleaseBatchPosted, ReleaseClaimed};
+use odra::casper_types::U256;
+use odra::prelude::*;
+
+/// Inner node of a release batch tree, as `crypto::verify_proof` hashes it:
+/// blake2b-256 of `0x01 || min(a, b) || max(a, b)`
+fn parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut hasher = Blake2bVar::new(32).unwrap();
+    hasher.update(&[0x01]);
+    hasher.update(&left);
+    hasher.update(&right);
+    let mut node = [0u8; 32];
+    hasher.finalize_variable(&mut node).unwrap();
+    node
+}
+
+/// Four releases of 1_000 * nonce to the user, their tree root and the
+/// proof of each leaf
+fn batch(s: &BridgeSetup) -> (Vec<ReleasePayload>, [u8; 32], Vec<Vec<[u8; 32]>>) {
+    let payloads: Vec<ReleasePayload> = (1..=4)
+        .map(|nonce| s.payload(nonce, U256::from(1_000 * nonce)))
+        .collect();
+    let leaves: Vec<[u8; 32]> = payloads
+        .iter()
+        .enumerate()
+        .map(|(index, payload)| s.bridge.get_release_leaf(index as u64, payload.clone()))
+        .collect();
+    let (left, right) = (parent(leaves[0], leaves[1]), parent(leaves[2], leaves[3]));
+    let proofs = vec![
+        vec![leaves[1], right],
+        vec![leaves[0], right],
+        vec![leaves[3], left],
+        vec![leaves[2], left],
+    ];
+    (payloads, parent(left, right), proofs)
+}
+
+#[test]
+fn operators_post_a_root_that_anyone_claims_from() {
+    let mut s = bridge_setup();
+    let second = s.env.get_account(4);
+    s.add_operator(second);
+    s.rotate(vec![s.operator, second], 2);
+    let (payloads, root, proofs) = batch(&s);
+
+    s.env.set_caller(s.user);
+    assert_eq!(s.bridge.try_attest_batch(root, 4), Err(Error::NotOperator.into()));
+
+    s.env.set_caller(s.operator);
+    s.bridge.attest_batch(root, 4);
+    assert_eq!(s.bridge.get_batch(root), None);
+    assert_eq!(
+        s.bridge.try_claim(root, 1, proofs[1].clone(), payloads[1].clone()),
+        Err(Error::UnknownBatch.into())
+    );
+
+    s.env.set_caller(second);
+    s.bridge.attest_batch(root, 4);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        ReleaseBatchPosted { root, leaf_count: 4, epoch: 1 }
+    ));
+    assert_eq!(
+        s.bridge.get_batch(root).map(|batch| batch.operators),
+        Some(vec![s.operator, second])
+    );
+
+    // A relayer claims on the user's behalf
+    s.env.set_caller(s.env.get_account(1));
+    s.bridge.claim(root, 1, proofs[1].clone(), payloads[1].clone());
+    assert_eq!(s.token_a.balance_of(&s.user), U256::from(2_000));
+    assert!(s.bridge.is_claimed(root, 1));
+    assert!(!s.bridge.is_claimed(root, 0));
+    assert!(s.bridge.is_processed(U256::from(SOURCE_CHAIN_ID), EVM_VAULT, U256::from(2)));
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        ReleaseClaimed { root, index: 1, transfer_id: payloads[1].transfer_id }
+    ));
+    assert_eq!(
+        s.bridge.try_claim(root, 1, proofs[1].clone(), payloads[1].clone()),
+        Err(Error::AlreadyClaimed.into())
+    );
+
+    s.env.set_caller(s.user);
+    s.bridge.claim(root, 3, proofs[3].clone(), payloads[3].clone());
+    assert_eq!(s.token_a.balance_of(&s.user), U256::from(6_000));
+}
+
+#[test]
+fn claims_need_a_valid_proof() {
+    let mut s = bridge_setup();
+    let (payloads, root, proofs) = batch(&s);
+
+    s.env.set_caller(s.operator);
+    assert_eq!(s.bridge.try_attest_batch(root, 0), Err(Error::EmptyBatch.into()));
+    s.bridge.attest_batch(root, 4);
+    assert_eq!(s.bridge.try_attest_batch(root, 4), Err(Error::AlreadyAttested.into()));
+
+    // Wrong proof, wrong index, past the end, tampered amount
+    let mut tampered = payloads[0].clone();
+    tampered.amount = U256::from(1_000_000);
+    for (index, proof, leaf) in [
+        (0, proofs[1].clone(), payloads[0].clone()),
+        (1, proofs[0].clone(), payloads[0].clone()),
+        (4, proofs[0].clone(), payloads[0].clone()),
+        (0, proofs[0].clone(), tampered),
+    ] {
+        assert_eq!(
+            s.bridge.try_claim(root, index, proof, leaf),
+            Err(Error::InvalidProof.into())
+        );
+    }
+    assert_eq!(s.token_a.balance_of(&s.user), U256::zero());
+}
+
+#[test]
+fn batches_expire_with_their_epoch() {
+    let mut s = bridge_setup();
+    let second = s.env.get_account(4);
+    s.add_operator(second);
+    let (payloads, root, proofs) = batch(&s);
+    s.env.set_caller(s.operator);
+    s.bridge.attest_batch(root, 4);
+
+    // Still claimable during the grace period after a rotation
+    s.rotate(vec![second], 1);
+    s.env.set_caller(s.user);
+    s.bridge.claim(root, 0, proofs[0].clone(), payloads[0].clone());
+    assert_eq!(s.token_a.balance_of(&s.user), U256::from(1_000));
+
+    s.env.advance_block_time(s.bridge.get_epoch_grace_period());
+    assert_eq!(
+        s.bridge.try_claim(root, 1, proofs[1].clone(), payloads[1].clone()),
+        Err(Error::BatchExpired.into())
+    );
+    assert!(!s.bridge.is_claimed(root, 1));
+
+    // The new set re-attests the unclaimed leaves in its own epoch
+    let (payloads, root, proofs) = batch(&s);
+    s.env.set_caller(second);
+    s.bridge.attest_batch(root, 4);
+    s.env.set_caller(s.user);
+    s.bridge.claim(root, 1, proofs[1].clone(), payloads[1].clone());
+    assert_eq!(s.token_a.balance_of(&s.user), U256::from(3_000));
+}
+
+#[test]
+fn released_transfers_cannot_be_claimed_again() {
+    let mut s = bridge_setup();
+    let (payloads, root, proofs) = batch(&s);
+
+    s.env.set_caller(s.operator);
+    s.bridge.attest_batch(root, 4);
+    s.bridge.receive_from_bridge(payloads[0].clone());
+    assert_eq!(
+        s.bridge.try_claim(root, 0, proofs[0].clone(), payloads[0].clone()),
+        Err(Error::NonceAlreadyProcessed.into())
+    );
+    assert!(!s.bridge.is_claimed(root, 0));
+}
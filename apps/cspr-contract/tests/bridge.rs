@@ -0,0 +1,1337 @@
+//! Integration tests for `AnchoreBridge`.
+
+mod common;
+
+use common::{
+    bridge_setup, recipient, source_tx_hash, BRIDGE_FUNDING, EVM_TOKEN_A, EVM_VAULT,
+    SOURCE_CHAIN_ID,
+};
+use cspr_contract::access::Role;
+use cspr_contract::bridge::{
+    BondConfig, Error, FeeConfig, OperatorSet, ReleaseStatus, RouteAccounting, TokenLimits,
+    TokenMode, TokenRoute, Unbonding,
+};
+use cspr_contract::bridged_token::{BridgedToken, BridgedTokenInitArgs, Error as BridgedTokenError};
+use cspr_contract::events::{
+    BridgeRelease, BridgeRequested, ChallengePeriodUpdated, EpochActivated, FeeConfigUpdated,
+    LimitsUpdated, OperatorSetProposed, OperatorSlashed, RateLimitHit, ReleaseQueued, RouteRemoved,
+    RouteUpdated, SwapFallback, TransferChallenged,
+};
+use odra::casper_types::bytesrepr::Bytes;
+use odra::casper_types::{PublicKey, U256};
+use odra::host::{Deployer, HostEnv};
+use odra::prelude::*;
+
+#[test]
+fn operators_come_from_the_registry() {
+    let mut s = bridge_setup();
+    let candidate = s.env.get_account(4);
+    assert_eq!(s.bridge.get_operator_registry(), s.registry.address());
+
+    assert!(s.bridge.is_operator(s.operator));
+    assert!(!s.bridge.is_operator(candidate));
+
+    // Registered operators only release once rotated into an epoch
+    s.add_operator(candidate);
+    assert!(!s.bridge.is_operator(candidate));
+    s.rotate(vec![s.operator, candidate], 1);
+    assert!(s.bridge.is_operator(candidate));
+
+    s.registry.remove_operator(candidate);
+    assert!(!s.bridge.is_operator(candidate));
+}
+
+#[test]
+fn only_operators_release() {
+    let mut s = bridge_setup();
+    let payload = s.payload(1, U256::from(1_000));
+
+    s.env.set_caller(s.user);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(payload.clone()),
+        Err(Error::NotOperator.into())
+    );
+
+    // A removed operator loses release rights
+    s.env.set_caller(s.admin);
+    s.registry.remove_operator(s.operator);
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(payload),
+        Err(Error::NotOperator.into())
+    );
+}
+
+#[test]
+fn direct_release_transfers_and_blocks_replay() {
+    let mut s = bridge_setup();
+    let (user, token) = (s.user, s.token_a.address());
+    let amount = U256::from(1_000_000);
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(s.payload(1, U256::zero())),
+        Err(Error::ZeroAmount.into())
+    );
+
+    s.bridge.receive_from_bridge(s.payload(1, amount));
+    assert_eq!(s.token_a.balance_of(&user), amount);
+    assert_eq!(
+        s.token_a.balance_of(&s.bridge.address()),
+        U256::from(BRIDGE_FUNDING) - amount
+    );
+    assert!(s.bridge.is_processed(U256::from(SOURCE_CHAIN_ID), EVM_VAULT, U256::one()));
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        BridgeRelease {
+            recipient: user,
+            amount,
+            fee: U256::zero(),
+            nonce: U256::one(),
+            token,
+            source_tx_hash: source_tx_hash(1),
+        }
+    ));
+
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(s.payload(1, amount)),
+        Err(Error::NonceAlreadyProcessed.into())
+    );
+}
+
+#[test]
+fn recipients_are_decoded_on_chain() {
+    let mut s = bridge_setup();
+    let user_hash = match s.user {
+        Address::Account(hash) => hash.value(),
+        _ => unreachable!(),
+    };
+    s.env.set_caller(s.operator);
+
+    // An account hash behind the 00 tag releases to the same account
+    let mut account = vec![0x00];
+    account.extend(user_hash);
+    let mut payload = s.payload(1, U256::from(1_000));
+    payload.recipient = Bytes::from(account);
+    s.bridge.receive_from_bridge(payload);
+    assert_eq!(s.token_a.balance_of(&s.user), U256::from(1_000));
+
+    let mut invalid_point = vec![0x02, 0x05];
+    invalid_point.extend([0u8; 32]);
+    for (bytes, error) in [
+        (vec![0x03; 33], Error::UnknownRecipientTag),
+        (vec![], Error::MalformedRecipient),
+        (vec![0x00; 10], Error::MalformedRecipient),
+        (vec![0x01; 5], Error::MalformedRecipient),
+        (invalid_point, Error::MalformedRecipient),
+    ] {
+        let mut payload = s.payload(2, U256::from(1_000));
+        payload.recipient = Bytes::from(bytes);
+        assert_eq!(s.bridge.try_receive_from_bridge(payload), Err(error.into()));
+    }
+    assert_eq!(s.token_a.balance_of(&s.user), U256::from(1_000));
+}
+
+#[test]
+fn nonces_are_scoped_to_source_chain_and_vault() {
+    let mut s = bridge_setup();
+    let amount = U256::from(1_000);
+    let other_chain = U256::from(84_532u64);
+    s.bridge
+        .set_route(other_chain, EVM_TOKEN_A, s.token_a.address(), 6, 6);
+
+    s.env.set_caller(s.operator);
+    let first = s.payload(1, amount);
+    s.bridge.receive_from_bridge(first.clone());
+
+    // Nonce 1 again, from a redeployed vault and from another chain
+    let mut redeployed = first.clone();
+    redeployed.source_contract = [0xefu8; 20];
+    let mut other = first.clone();
+    other.source_chain_id = other_chain;
+    assert!(!s.bridge.is_processed(other_chain, EVM_VAULT, U256::one()));
+
+    s.bridge.receive_from_bridge(redeployed);
+    s.bridge.receive_from_bridge(other);
+    assert_eq!(s.token_a.balance_of(&s.user), amount * 3);
+    assert!(s.bridge.is_processed(other_chain, EVM_VAULT, U256::one()));
+    assert!(s.bridge.is_processed(U256::from(SOURCE_CHAIN_ID), [0xefu8; 20], U256::one()));
+
+    // The key ignores everything but chain, vault and nonce
+    let mut replay = first;
+    replay.transfer_id = [0xaau8; 32];
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(replay),
+        Err(Error::NonceAlreadyProcessed.into())
+    );
+}
+
+#[test]
+fn swap_release_routes_through_pool() {
+    let mut s = bridge_setup();
+    let (user, token) = (s.user, s.token_a.address());
+    let amount = U256::from(1_000_000);
+    let expected = s.pool.get_amount_out(amount, token);
+    assert_eq!(s.bridge.get_pool(s.token_b.address(), token), Some(s.pool.address()));
+
+    let mut payload = s.payload(2, amount);
+    payload.target_token = Some(s.token_b.address());
+    payload.min_amount_out = expected;
+    s.env.set_caller(s.operator);
+    s.bridge.receive_from_bridge(payload);
+
+    assert_eq!(s.token_a.balance_of(&user), U256::zero());
+    assert_eq!(s.token_b.balance_of(&user), expected);
+    assert_eq!(
+        s.token_a.balance_of(&s.bridge.address()),
+        U256::from(BRIDGE_FUNDING) - amount
+    );
+}
+
+#[test]
+fn swap_release_falls_back_to_bridged_token() {
+    let mut s = bridge_setup();
+    let (user, token, target) = (s.user, s.token_a.address(), s.token_b.address());
+    let amount = U256::from(1_000_000);
+    let quoted_out = s.pool.get_amount_out(amount, token);
+
+    // The quote misses the minimum: the release still goes through, unswapped
+    let mut payload = s.payload(1, amount);
+    payload.target_token = Some(target);
+    payload.min_amount_out = quoted_out + 1;
+    s.env.set_caller(s.operator);
+    s.bridge.receive_from_bridge(payload.clone());
+
+    assert_eq!(s.token_a.balance_of(&user), amount);
+    assert_eq!(s.token_b.balance_of(&user), U256::zero());
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        SwapFallback {
+            transfer_id: payload.transfer_id,
+            token,
+            target_token: target,
+            amount,
+            quoted_out,
+            min_amount_out: quoted_out + 1,
+        }
+    ));
+
+    // No pool for the pair
+    let unpooled = s.env.get_account(7);
+    let mut payload = s.payload(2, amount);
+    payload.target_token = Some(unpooled);
+    s.bridge.receive_from_bridge(payload);
+    assert_eq!(s.token_a.balance_of(&user), amount * 2);
+}
+
+#[test]
+fn admin_proposes_and_approves_operator_sets() {
+    let mut s = bridge_setup();
+    let (operator, second) = (s.operator, s.env.get_account(4));
+    assert_eq!(s.bridge.get_epoch(), 0);
+    assert_eq!(
+        s.bridge.get_operator_set(0),
+        Some(OperatorSet { operators: vec![operator], threshold: 1 })
+    );
+    assert_eq!(s.bridge.get_threshold(), 1);
+
+    assert_eq!(
+        s.bridge.try_propose_operator_set(vec![operator], 0),
+        Err(Error::InvalidThreshold.into())
+    );
+    assert_eq!(
+        s.bridge.try_propose_operator_set(vec![operator], 2),
+        Err(Error::InvalidThreshold.into())
+    );
+    // Operators must be registered, and listed once
+    assert_eq!(
+        s.bridge.try_propose_operator_set(vec![operator, second], 2),
+        Err(Error::InvalidOperatorSet.into())
+    );
+    s.add_operator(second);
+    assert_eq!(
+        s.bridge.try_propose_operator_set(vec![operator, operator], 2),
+        Err(Error::InvalidOperatorSet.into())
+    );
+    assert_eq!(s.bridge.try_approve_operator_set(), Err(Error::NoProposedOperatorSet.into()));
+
+    s.bridge.propose_operator_set(vec![operator, second], 2);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        OperatorSetProposed { epoch: 1, operators: vec![operator, second], threshold: 2 }
+    ));
+    s.env.set_caller(operator);
+    assert_eq!(s.bridge.try_approve_operator_set(), Err(Error::Unauthorized.into()));
+    assert_eq!(
+        s.bridge.try_propose_operator_set(vec![operator], 1),
+        Err(Error::Unauthorized.into())
+    );
+
+    s.env.set_caller(s.admin);
+    s.bridge.approve_operator_set();
+    assert_eq!(s.bridge.get_epoch(), 1);
+    assert_eq!(s.bridge.get_threshold(), 2);
+    assert_eq!(s.bridge.get_proposed_operator_set(), None);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        EpochActivated {
+            epoch: 1,
+            operators: vec![operator, second],
+            threshold: 2,
+            approved_by_admin: true,
+        }
+    ));
+}
+
+#[test]
+fn current_operators_sign_the_handover() {
+    let mut s = bridge_setup();
+    let (operator, second, outsider) = (s.operator, s.env.get_account(4), s.env.get_account(5));
+    s.add_operator(second);
+    s.rotate(vec![operator, second], 2);
+
+    // Hand epoch 1 over to the second operator alone
+    s.bridge.propose_operator_set(vec![second], 1);
+    let message = s.bridge.get_handover_message();
+    let first_sig = sign(&s.env, operator, &message);
+    let second_sig = sign(&s.env, second, &message);
+
+    s.env.set_caller(outsider);
+    assert_eq!(
+        s.bridge.try_handover_operator_set(vec![first_sig.clone()]),
+        Err(Error::ThresholdNotMet.into())
+    );
+    assert_eq!(
+        s.bridge.try_handover_operator_set(vec![
+            first_sig.clone(),
+            sign(&s.env, outsider, &message)
+        ]),
+        Err(Error::NotOperator.into())
+    );
+    // Release signatures don't authorize a handover
+    let payload = s.payload(1, U256::from(1_000));
+    let release_message = s.bridge.get_release_message(payload);
+    assert_eq!(
+        s.bridge.try_handover_operator_set(vec![
+            first_sig.clone(),
+            sign(&s.env, second, &release_message)
+        ]),
+        Err(Error::InvalidSignature.into())
+    );
+
+    s.bridge.handover_operator_set(vec![first_sig, second_sig]);
+    assert_eq!(s.bridge.get_epoch(), 2);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        EpochActivated { epoch: 2, operators: vec![second], threshold: 1, approved_by_admin: false }
+    ));
+    assert!(!s.bridge.is_operator(operator));
+    assert!(s.bridge.is_operator(second));
+}
+
+#[test]
+fn previous_epoch_is_accepted_during_the_grace_period() {
+    let mut s = bridge_setup();
+    let (operator, second) = (s.operator, s.env.get_account(4));
+    let amount = U256::from(1_000);
+    let in_flight = s.payload(1, amount);
+    let late = s.payload(2, amount);
+
+    s.add_operator(second);
+    s.rotate(vec![second], 1);
+    assert_eq!(s.bridge.get_epoch_grace_period(), HOUR);
+
+    // The old set still releases epoch 0 transfers, but not the new epoch's
+    s.env.set_caller(operator);
+    s.bridge.receive_from_bridge(in_flight);
+    assert_eq!(s.token_a.balance_of(&s.user), amount);
+    let current = s.payload(3, amount);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(current.clone()),
+        Err(Error::NotOperator.into())
+    );
+
+    s.env.advance_block_time(HOUR);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(late.clone()),
+        Err(Error::EpochNotAccepted.into())
+    );
+    s.env.set_caller(second);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(late),
+        Err(Error::EpochNotAccepted.into())
+    );
+
+    let mut future = s.payload(4, amount);
+    future.epoch += 1;
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(future),
+        Err(Error::EpochNotAccepted.into())
+    );
+    s.bridge.receive_from_bridge(current);
+    assert_eq!(s.token_a.balance_of(&s.user), amount * 2);
+}
+
+#[test]
+fn attestations_release_at_threshold() {
+    let mut s = bridge_setup();
+    let second = s.env.get_account(4);
+    let user = s.user;
+    s.add_operator(second);
+    s.rotate(vec![s.operator, second], 2);
+
+    let payload = s.payload(1, U256::from(1_000_000));
+    let mut conflicting = payload.clone();
+    conflicting.amount += U256::one();
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(payload.clone()),
+        Err(Error::AttestationRequired.into())
+    );
+
+    s.bridge.attest(payload.clone());
+    assert_eq!(s.token_a.balance_of(&user), U256::zero());
+    assert_eq!(
+        s.bridge.try_attest(payload.clone()),
+        Err(Error::AlreadyAttested.into())
+    );
+
+    // A second operator attesting a different amount starts its own tally
+    s.env.set_caller(second);
+    s.bridge.attest(conflicting.clone());
+    assert_eq!(s.token_a.balance_of(&user), U256::zero());
+    assert_eq!(s.bridge.get_attestation_count(payload.clone()), 1);
+
+    s.bridge.attest(payload.clone());
+    assert_eq!(s.token_a.balance_of(&user), payload.amount);
+    assert!(s.bridge.is_processed(U256::from(SOURCE_CHAIN_ID), EVM_VAULT, U256::one()));
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_attest(conflicting),
+        Err(Error::NonceAlreadyProcessed.into())
+    );
+}
+
+fn sign(env: &HostEnv, signer: Address, message: &Bytes) -> (PublicKey, Bytes) {
+    (env.public_key(&signer), env.sign_message(message, &signer))
+}
+
+#[test]
+fn anyone_can_relay_a_threshold_signed_release() {
+    let mut s = bridge_setup();
+    let second = s.env.get_account(4);
+    let outsider = s.env.get_account(5);
+    s.add_operator(second);
+    s.rotate(vec![s.operator, second], 2);
+
+    let payload = s.payload(3, U256::from(1_000_000));
+    let message = s.bridge.get_release_message(payload.clone());
+    let first_sig = sign(&s.env, s.operator, &message);
+    let second_sig = sign(&s.env, second, &message);
+
+    // Relayed by an account that is neither admin nor operator
+    s.env.set_caller(outsider);
+    assert_eq!(
+        s.bridge.try_receive_with_signatures(payload.clone(), vec![first_sig.clone()]),
+        Err(Error::ThresholdNotMet.into())
+    );
+    assert_eq!(
+        s.bridge.try_receive_with_signatures(
+            payload.clone(),
+            vec![first_sig.clone(), first_sig.clone()]
+        ),
+        Err(Error::DuplicateSigner.into())
+    );
+    assert_eq!(
+        s.bridge.try_receive_with_signatures(
+            payload.clone(),
+            vec![first_sig.clone(), sign(&s.env, outsider, &message)]
+        ),
+        Err(Error::NotOperator.into())
+    );
+
+    // A signature over a different payload doesn't verify
+    let mut tampered = payload.clone();
+    tampered.amount = U256::from(2_000_000);
+    assert_eq!(
+        s.bridge.try_receive_with_signatures(
+            tampered,
+            vec![first_sig.clone(), second_sig.clone()]
+        ),
+        Err(Error::InvalidSignature.into())
+    );
+
+    s.bridge
+        .receive_with_signatures(payload.clone(), vec![first_sig.clone(), second_sig.clone()]);
+    assert_eq!(s.token_a.balance_of(&s.user), payload.amount);
+
+    assert_eq!(
+        s.bridge.try_receive_with_signatures(payload, vec![first_sig, second_sig]),
+        Err(Error::NonceAlreadyProcessed.into())
+    );
+}
+
+#[test]
+fn bridge_out_escrows_and_emits_vault_fields() {
+    let mut s = bridge_setup();
+    let sender = s.env.get_account(1);
+    let (token, amount) = (s.token_a.address(), U256::from(5_000));
+    let evm_recipient = [0xabu8; 20];
+    let chain_id = U256::from(11_155_111u64);
+
+    s.env.set_caller(sender);
+    s.token_a.approve(&s.bridge.address(), &amount);
+    assert_eq!(
+        s.bridge.try_bridge_out(token, U256::zero(), evm_recipient, chain_id),
+        Err(Error::ZeroAmount.into())
+    );
+    assert_eq!(
+        s.bridge.try_bridge_out(token, amount, [0u8; 20], chain_id),
+        Err(Error::InvalidRecipient.into())
+    );
+
+    let escrow_before = s.token_a.balance_of(&s.bridge.address());
+    s.bridge.bridge_out(token, amount, evm_recipient, chain_id);
+
+    assert_eq!(s.token_a.balance_of(&s.bridge.address()), escrow_before + amount);
+    assert_eq!(s.bridge.get_outbound_nonce(), U256::one());
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        BridgeRequested {
+            sender,
+            token,
+            amount,
+            evm_recipient,
+            nonce: U256::zero(),
+            dest_chain_id: chain_id,
+        }
+    ));
+}
+
+#[test]
+fn unregistered_tokens_are_rejected() {
+    let mut s = bridge_setup();
+    let token_b = s.token_b.address();
+    assert_eq!(s.bridge.get_token_mode(token_b), None);
+
+    // A route alone doesn't make a token releasable
+    let evm_token_b = [0x22u8; 20];
+    s.bridge
+        .set_route(U256::from(SOURCE_CHAIN_ID), evm_token_b, token_b, 6, 6);
+    let mut payload = s.payload(1, U256::from(1_000));
+    payload.evm_token = evm_token_b;
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(payload),
+        Err(Error::UnsupportedToken.into())
+    );
+}
+
+#[test]
+fn wrapped_tokens_are_minted_and_burned() {
+    let mut s = bridge_setup();
+    let user = s.user;
+    let mut wrapped = BridgedToken::deploy(
+        &s.env,
+        BridgedTokenInitArgs {
+            name: "Bridged USDC".to_string(),
+            symbol: "bUSDC".to_string(),
+            decimals: 6,
+            bridge: s.bridge.address(),
+        },
+    );
+    let evm_usdc = [0x33u8; 20];
+    s.bridge
+        .set_token_mode(wrapped.address(), TokenMode::MintBurn);
+    s.bridge
+        .set_route(U256::from(SOURCE_CHAIN_ID), evm_usdc, wrapped.address(), 6, 6);
+
+    // Only the bridge may mint
+    assert_eq!(
+        wrapped.try_mint(&user, &U256::from(1)),
+        Err(BridgedTokenError::NotBridge.into())
+    );
+
+    let amount = U256::from(1_000_000);
+    s.env.set_caller(s.operator);
+    let mut payload = s.payload(1, amount);
+    payload.evm_token = evm_usdc;
+    s.bridge.receive_from_bridge(payload);
+    assert_eq!(wrapped.balance_of(&user), amount);
+    assert_eq!(wrapped.total_supply(), amount);
+
+    s.env.set_caller(user);
+    s.bridge
+        .bridge_out(wrapped.address(), amount, [0xabu8; 20], U256::from(SOURCE_CHAIN_ID));
+    assert_eq!(wrapped.balance_of(&user), U256::zero());
+    assert_eq!(wrapped.total_supply(), U256::zero());
+}
+
+#[test]
+fn routes_are_admin_managed() {
+    let mut s = bridge_setup();
+    let chain_id = U256::from(SOURCE_CHAIN_ID);
+    let (token_a, evm_token) = (s.token_a.address(), [0x44u8; 20]);
+
+    assert_eq!(
+        s.bridge.get_route(chain_id, EVM_TOKEN_A),
+        Some(TokenRoute { token: token_a, source_decimals: 6, local_decimals: 6 })
+    );
+    assert_eq!(
+        s.bridge.try_set_route(chain_id, evm_token, token_a, 33, 6),
+        Err(Error::InvalidDecimals.into())
+    );
+
+    s.bridge.set_route(chain_id, evm_token, token_a, 18, 6);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        RouteUpdated {
+            source_chain_id: chain_id,
+            evm_token,
+            token: token_a,
+            source_decimals: 18,
+            local_decimals: 6,
+        }
+    ));
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_set_route(chain_id, evm_token, token_a, 6, 6),
+        Err(Error::Unauthorized.into())
+    );
+    assert_eq!(
+        s.bridge.try_remove_route(chain_id, evm_token),
+        Err(Error::Unauthorized.into())
+    );
+
+    s.env.set_caller(s.admin);
+    s.bridge.remove_route(chain_id, evm_token);
+    assert_eq!(s.bridge.get_route(chain_id, evm_token), None);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        RouteRemoved { source_chain_id: chain_id, evm_token }
+    ));
+    assert_eq!(
+        s.bridge.try_remove_route(chain_id, evm_token),
+        Err(Error::UnknownRoute.into())
+    );
+}
+
+#[test]
+fn releases_resolve_route_and_scale_decimals() {
+    let mut s = bridge_setup();
+    let chain_id = U256::from(SOURCE_CHAIN_ID);
+    let evm_weth = [0x55u8; 20];
+    s.bridge
+        .set_route(chain_id, evm_weth, s.token_a.address(), 18, 6);
+
+    s.env.set_caller(s.operator);
+    let mut unknown = s.payload(1, U256::from(1_000));
+    unknown.source_chain_id = U256::one();
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(unknown),
+        Err(Error::UnknownRoute.into())
+    );
+
+    // Amounts that don't convert exactly revert instead of stranding dust
+    let mut payload = s.payload(1, U256::from(2_500_000_000_000_000_001u128));
+    payload.evm_token = evm_weth;
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(payload.clone()),
+        Err(Error::InexactAmount.into())
+    );
+
+    // 2.5 units at 18 decimals arrive as 2_500_000 at 6
+    payload.amount = U256::from(2_500_000_000_000_000_000u128);
+    s.bridge.receive_from_bridge(payload);
+    assert_eq!(s.token_a.balance_of(&s.user), U256::from(2_500_000));
+}
+
+#[test]
+fn bridge_out_follows_the_route_decimals() {
+    let mut s = bridge_setup();
+    let (user, token) = (s.user, s.token_a.address());
+    let chain_id = U256::from(SOURCE_CHAIN_ID);
+    let evm_weth = [0x55u8; 20];
+    s.bridge.set_route(chain_id, evm_weth, token, 18, 6);
+    assert_eq!(s.bridge.get_outbound_route(chain_id, token), Some(evm_weth));
+    s.token_a.mint(&user, &U256::from(2_000_000));
+
+    s.env.set_caller(user);
+    s.token_a.approve(&s.bridge.address(), &U256::from(2_000_000));
+    assert_eq!(
+        s.bridge.try_bridge_out(token, U256::from(1_000_000), [0xabu8; 20], U256::one()),
+        Err(Error::UnknownRoute.into())
+    );
+
+    // 1 unit at 6 decimals leaves as 1 unit at 18
+    s.bridge.bridge_out(token, U256::from(1_000_000), [0xabu8; 20], chain_id);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        BridgeRequested {
+            sender: user,
+            token,
+            amount: U256::from(1_000_000_000_000_000_000u128),
+            evm_recipient: [0xabu8; 20],
+            nonce: U256::zero(),
+            dest_chain_id: chain_id,
+        }
+    ));
+
+    // Removing the route stops outbound transfers of the token
+    s.env.set_caller(s.admin);
+    s.bridge.remove_route(chain_id, evm_weth);
+    assert_eq!(s.bridge.get_outbound_route(chain_id, token), None);
+    s.env.set_caller(user);
+    assert_eq!(
+        s.bridge.try_bridge_out(token, U256::from(1_000_000), [0xabu8; 20], chain_id),
+        Err(Error::UnknownRoute.into())
+    );
+}
+
+const HOUR: u64 = 60 * 60 * 1000;
+
+#[test]
+fn limits_are_admin_set_and_validated() {
+    let mut s = bridge_setup();
+    let token = s.token_a.address();
+    assert_eq!(s.bridge.get_limits(token), None);
+    assert_eq!(s.bridge.get_remaining_capacity(token), U256::MAX);
+
+    let unbounded_window = TokenLimits {
+        window_cap: U256::from(1_000),
+        ..Default::default()
+    };
+    assert_eq!(
+        s.bridge.try_set_limits(token, unbounded_window),
+        Err(Error::InvalidLimits.into())
+    );
+
+    let limits = TokenLimits {
+        window_cap: U256::from(1_000),
+        window_duration: HOUR,
+        max_per_transfer: U256::from(500),
+        large_transfer_threshold: U256::from(400),
+        delay: HOUR,
+    };
+    s.bridge.set_limits(token, limits.clone());
+    assert_eq!(s.bridge.get_limits(token), Some(limits));
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        LimitsUpdated {
+            token,
+            window_cap: U256::from(1_000),
+            window_duration: HOUR,
+            max_per_transfer: U256::from(500),
+            large_transfer_threshold: U256::from(400),
+            delay: HOUR,
+        }
+    ));
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_set_limits(token, TokenLimits::default()),
+        Err(Error::Unauthorized.into())
+    );
+}
+
+#[test]
+fn transfers_above_maximum_revert() {
+    let mut s = bridge_setup();
+    let limits = TokenLimits {
+        max_per_transfer: U256::from(1_000),
+        ..Default::default()
+    };
+    s.bridge.set_limits(s.token_a.address(), limits);
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(s.payload(1, U256::from(1_001))),
+        Err(Error::TransferAboveMaximum.into())
+    );
+    s.bridge.receive_from_bridge(s.payload(1, U256::from(1_000)));
+    assert_eq!(s.token_a.balance_of(&s.user), U256::from(1_000));
+}
+
+#[test]
+fn window_cap_queues_overflow_until_usage_decays() {
+    let mut s = bridge_setup();
+    let token = s.token_a.address();
+    let amount = U256::from(1_000);
+    let limits = TokenLimits {
+        window_cap: U256::from(1_500),
+        window_duration: HOUR,
+        ..Default::default()
+    };
+    s.bridge.set_limits(token, limits);
+
+    s.env.set_caller(s.operator);
+    s.bridge.receive_from_bridge(s.payload(1, amount));
+    assert_eq!(s.bridge.get_window_usage(token), amount);
+    assert_eq!(s.bridge.get_remaining_capacity(token), U256::from(500));
+
+    // Over the cap: held pending rather than paid, and flagged for monitoring
+    let overflow = s.payload(2, amount);
+    s.bridge.receive_from_bridge(overflow.clone());
+    assert_eq!(s.token_a.balance_of(&s.user), amount);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        RateLimitHit { token, amount, window_used: amount, window_cap: U256::from(1_500) }
+    ));
+    assert!(s.bridge.get_pending_release(overflow.transfer_id).is_some());
+    assert_eq!(
+        s.bridge.try_finalize(overflow.transfer_id),
+        Err(Error::RateLimitExceeded.into())
+    );
+
+    // The window rolls: a sixth of an hour frees a sixth of the cap, not all of it
+    s.env.advance_block_time(HOUR / 6);
+    assert_eq!(s.bridge.get_window_usage(token), U256::from(750));
+    assert_eq!(
+        s.bridge.try_finalize(overflow.transfer_id),
+        Err(Error::RateLimitExceeded.into())
+    );
+
+    s.env.advance_block_time(HOUR / 6);
+    assert_eq!(s.bridge.get_window_usage(token), U256::from(500));
+    s.bridge.finalize(overflow.transfer_id);
+    assert_eq!(s.token_a.balance_of(&s.user), amount * 2);
+    assert_eq!(s.bridge.get_window_usage(token), U256::from(1_500));
+    assert_eq!(s.bridge.get_pending_release(overflow.transfer_id), None);
+
+    s.env.advance_block_time(HOUR);
+    assert_eq!(s.bridge.get_window_usage(token), U256::zero());
+}
+
+#[test]
+fn large_transfers_wait_for_the_challenge_period() {
+    let mut s = bridge_setup();
+    let (token, user) = (s.token_a.address(), s.user);
+    let amount = U256::from(10_000);
+    let limits = TokenLimits {
+        large_transfer_threshold: amount,
+        ..Default::default()
+    };
+    s.bridge.set_limits(token, limits);
+
+    s.env.set_caller(s.operator);
+    let payload = s.payload(1, amount);
+    s.bridge.receive_from_bridge(payload.clone());
+    assert_eq!(s.token_a.balance_of(&user), U256::zero());
+    assert!(s.bridge.is_processed(U256::from(SOURCE_CHAIN_ID), EVM_VAULT, U256::one()));
+
+    let pending = s.bridge.get_pending_release(payload.transfer_id).unwrap();
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        ReleaseQueued {
+            transfer_id: payload.transfer_id,
+            token,
+            recipient: user,
+            amount,
+            unlock_at: pending.unlock_at,
+        }
+    ));
+
+    // Anyone can finalize, but only once the default day has passed
+    s.env.set_caller(s.env.get_account(5));
+    assert_eq!(
+        s.bridge.try_finalize(payload.transfer_id),
+        Err(Error::ReleaseLocked.into())
+    );
+    s.env.advance_block_time(24 * HOUR - 1);
+    assert_eq!(
+        s.bridge.try_finalize(payload.transfer_id),
+        Err(Error::ReleaseLocked.into())
+    );
+    s.env.advance_block_time(1);
+    s.bridge.finalize(payload.transfer_id);
+    assert_eq!(s.token_a.balance_of(&user), amount);
+    assert_eq!(
+        s.bridge.try_finalize(payload.transfer_id),
+        Err(Error::NotPending.into())
+    );
+}
+
+#[test]
+fn fee_config_is_admin_set_and_validated() {
+    let mut s = bridge_setup();
+    let chain_id = U256::from(SOURCE_CHAIN_ID);
+    let config = FeeConfig { fee_bps: 10_001, ..Default::default() };
+    assert_eq!(
+        s.bridge.try_set_fee_config(chain_id, EVM_TOKEN_A, config),
+        Err(Error::InvalidFeeConfig.into())
+    );
+    // The treasury and LP shares can't exceed the whole fee
+    let config = FeeConfig { treasury_share_bps: 6_000, lp_share_bps: 4_001, ..Default::default() };
+    assert_eq!(
+        s.bridge.try_set_fee_config(chain_id, EVM_TOKEN_A, config),
+        Err(Error::InvalidFeeConfig.into())
+    );
+
+    let config = FeeConfig {
+        fee_bps: 30,
+        min_fee: U256::from(100),
+        treasury_share_bps: 6_000,
+        lp_share_bps: 4_000,
+    };
+    s.bridge.set_fee_config(chain_id, EVM_TOKEN_A, config.clone());
+    assert_eq!(s.bridge.get_fee_config(chain_id, EVM_TOKEN_A), config);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        FeeConfigUpdated {
+            source_chain_id: chain_id,
+            evm_token: EVM_TOKEN_A,
+            fee_bps: 30,
+            min_fee: U256::from(100),
+            treasury_share_bps: 6_000,
+            lp_share_bps: 4_000,
+        }
+    ));
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_set_fee_config(chain_id, EVM_TOKEN_A, FeeConfig::default()),
+        Err(Error::Unauthorized.into())
+    );
+    assert_eq!(
+        s.bridge.try_claim_operator_fees(s.token_a.address()),
+        Err(Error::NothingToClaim.into())
+    );
+}
+
+#[test]
+fn release_fees_accrue_to_operator_and_treasury() {
+    let mut s = bridge_setup();
+    let (token, user, operator) = (s.token_a.address(), s.user, s.operator);
+    let treasury = s.env.get_account(6);
+    let treasury_balance = s.token_a.balance_of(&treasury);
+    s.bridge.set_treasury(treasury);
+    let config = FeeConfig {
+        fee_bps: 100,
+        min_fee: U256::from(10),
+        treasury_share_bps: 2_000,
+        ..Default::default()
+    };
+    s.bridge
+        .set_fee_config(U256::from(SOURCE_CHAIN_ID), EVM_TOKEN_A, config);
+
+    // 1% of 1_000_000, a fifth of it for the treasury
+    s.env.set_caller(operator);
+    s.bridge.receive_from_bridge(s.payload(1, U256::from(1_000_000)));
+    assert_eq!(s.token_a.balance_of(&user), U256::from(990_000));
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        BridgeRelease {
+            recipient: user,
+            amount: U256::from(990_000),
+            fee: U256::from(10_000),
+            nonce: U256::one(),
+            token,
+            source_tx_hash: source_tx_hash(1),
+        }
+    ));
+    assert_eq!(s.bridge.get_operator_fees(operator, token), U256::from(8_000));
+    assert_eq!(s.bridge.get_treasury_fees(token), U256::from(2_000));
+
+    // The flat minimum applies to small releases, and must leave something over
+    s.bridge.receive_from_bridge(s.payload(2, U256::from(100)));
+    assert_eq!(s.token_a.balance_of(&user), U256::from(990_090));
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(s.payload(3, U256::from(10))),
+        Err(Error::FeeExceedsAmount.into())
+    );
+
+    s.bridge.claim_operator_fees(token);
+    assert_eq!(s.token_a.balance_of(&operator), U256::from(8_008));
+    assert_eq!(s.bridge.get_operator_fees(operator, token), U256::zero());
+
+    s.env.set_caller(user);
+    s.bridge.claim_treasury_fees(token);
+    assert_eq!(s.token_a.balance_of(&treasury), treasury_balance + U256::from(2_002));
+    assert_eq!(
+        s.bridge.try_claim_treasury_fees(token),
+        Err(Error::NothingToClaim.into())
+    );
+}
+
+#[test]
+fn attestation_fees_are_split_between_attesters() {
+    let mut s = bridge_setup();
+    let token = s.token_a.address();
+    let second = s.env.get_account(4);
+    s.add_operator(second);
+    s.rotate(vec![s.operator, second], 2);
+    let config = FeeConfig { fee_bps: 100, ..Default::default() };
+    s.bridge
+        .set_fee_config(U256::from(SOURCE_CHAIN_ID), EVM_TOKEN_A, config);
+
+    let payload = s.payload(1, U256::from(1_000_100));
+    s.env.set_caller(s.operator);
+    s.bridge.attest(payload.clone());
+    s.env.set_caller(second);
+    s.bridge.attest(payload);
+
+    // A 10_001 fee: 5_000 each, the odd unit goes to the treasury
+    assert_eq!(s.bridge.get_operator_fees(s.operator, token), U256::from(5_000));
+    assert_eq!(s.bridge.get_operator_fees(second, token), U256::from(5_000));
+    assert_eq!(s.bridge.get_treasury_fees(token), U256::one());
+}
+
+const MIN_BOND: u64 = 1_000;
+
+/// Requires bonds in token_b and bonds `MIN_BOND` for the operator
+fn bond_operator(s: &mut common::BridgeSetup) {
+    let config = BondConfig {
+        token: s.token_b.address(),
+        min_bond: U256::from(MIN_BOND),
+        unbonding_delay: HOUR,
+    };
+    s.bridge.set_bond_config(config);
+
+    s.token_b.mint(&s.operator, &U256::from(MIN_BOND));
+    s.env.set_caller(s.operator);
+    s.token_b.approve(&s.bridge.address(), &U256::from(MIN_BOND));
+    s.bridge.bond(U256::from(MIN_BOND));
+    s.env.set_caller(s.admin);
+}
+
+#[test]
+fn operators_must_stay_bonded_to_release() {
+    let mut s = bridge_setup();
+    let operator = s.operator;
+    s.bridge.set_bond_config(BondConfig {
+        token: s.token_b.address(),
+        min_bond: U256::from(MIN_BOND),
+        unbonding_delay: HOUR,
+    });
+    assert!(!s.bridge.is_operator(operator));
+
+    s.env.set_caller(operator);
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(s.payload(1, U256::from(1_000))),
+        Err(Error::NotOperator.into())
+    );
+
+    s.env.set_caller(s.admin);
+    bond_operator(&mut s);
+    assert!(s.bridge.is_operator(operator));
+    assert_eq!(s.bridge.get_bond(operator), U256::from(MIN_BOND));
+
+    s.env.set_caller(operator);
+    s.bridge.receive_from_bridge(s.payload(1, U256::from(1_000)));
+
+    // Unbonding below the minimum deactivates at once, funds follow after the delay
+    s.bridge.start_unbonding(U256::from(400));
+    assert!(!s.bridge.is_operator(operator));
+    let unbonding = s.bridge.get_unbonding(operator).unwrap();
+    assert_eq!(unbonding.amount, U256::from(400));
+    assert_eq!(
+        s.bridge.try_start_unbonding(U256::from(100)),
+        Err(Error::UnbondingInProgress.into())
+    );
+    assert_eq!(s.bridge.try_withdraw_bond(), Err(Error::UnbondingInProgress.into()));
+
+    s.env.advance_block_time(HOUR);
+    s.bridge.withdraw_bond();
+    assert_eq!(s.token_b.balance_of(&operator), U256::from(400));
+    assert_eq!(s.bridge.get_unbonding(operator), None::<Unbonding>);
+    assert_eq!(s.bridge.get_bond(operator), U256::from(600));
+}
+
+#[test]
+fn bond_token_is_fixed_once_set() {
+    let mut s = bridge_setup();
+    bond_operator(&mut s);
+    let config = BondConfig {
+        token: s.token_a.address(),
+        min_bond: U256::zero(),
+        unbonding_delay: 0,
+    };
+    assert_eq!(
+        s.bridge.try_set_bond_config(config),
+        Err(Error::InvalidBondConfig.into())
+    );
+}
+
+#[test]
+fn conflicting_signatures_slash_the_bond() {
+    let mut s = bridge_setup();
+    let (operator, beneficiary) = (s.operator, s.env.get_account(6));
+    bond_operator(&mut s);
+
+    let payload = s.payload(1, U256::from(1_000));
+    let mut conflicting = payload.clone();
+    conflicting.recipient = recipient(&s.env, s.env.get_account(5));
+    let (public_key, first_sig) =
+        sign(&s.env, operator, &s.bridge.get_release_message(payload.clone()));
+    let (_, second_sig) =
+        sign(&s.env, operator, &s.bridge.get_release_message(conflicting.clone()));
+
+    // The same release signed again in a later epoch isn't a conflict
+    let mut resigned = payload.clone();
+    resigned.epoch += 1;
+    let (_, resigned_sig) =
+        sign(&s.env, operator, &s.bridge.get_release_message(resigned.clone()));
+    assert_eq!(
+        s.bridge.try_slash(
+            public_key.clone(),
+            payload.clone(),
+            first_sig.clone(),
+            resigned,
+            resigned_sig,
+            beneficiary
+        ),
+        Err(Error::NoConflict.into())
+    );
+
+    // Different transfers aren't a conflict
+    let other = s.payload(2, U256::from(1_000));
+    let (_, other_sig) = sign(&s.env, operator, &s.bridge.get_release_message(other.clone()));
+    assert_eq!(
+        s.bridge.try_slash(
+            public_key.clone(),
+            payload.clone(),
+            first_sig.clone(),
+            other,
+            other_sig,
+            beneficiary
+        ),
+        Err(Error::NoConflict.into())
+    );
+    assert_eq!(
+        s.bridge.try_slash(
+            public_key.clone(),
+            payload.clone(),
+            first_sig.clone(),
+            conflicting.clone(),
+            first_sig.clone(),
+            beneficiary
+        ),
+        Err(Error::InvalidSignature.into())
+    );
+
+    s.env.set_caller(operator);
+    assert_eq!(
+        s.bridge.try_slash(
+            public_key.clone(),
+            payload.clone(),
+            first_sig.clone(),
+            conflicting.clone(),
+            second_sig.clone(),
+            beneficiary
+        ),
+        Err(Error::Unauthorized.into())
+    );
+
+    s.env.set_caller(s.admin);
+    s.bridge
+        .slash(public_key, payload, first_sig, conflicting, second_sig, beneficiary);
+    assert_eq!(s.token_b.balance_of(&beneficiary), U256::from(MIN_BOND));
+    assert_eq!(s.bridge.get_bond(operator), U256::zero());
+    assert!(!s.registry.is_operator(operator));
+    assert!(!s.bridge.is_operator(operator));
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        OperatorSlashed { operator, amount: U256::from(MIN_BOND), beneficiary }
+    ));
+}
+
+/// Holds releases of 10_000 or more of token_a pending for a day
+fn hold_large_releases(s: &mut common::BridgeSetup) {
+    let limits = TokenLimits {
+        large_transfer_threshold: U256::from(10_000),
+        ..Default::default()
+    };
+    s.bridge.set_limits(s.token_a.address(), limits);
+    s.bridge.set_challenge_period(24 * HOUR);
+}
+
+#[test]
+fn challenge_period_is_separate_from_the_rate_limit_delay() {
+    let mut s = bridge_setup();
+    let token = s.token_a.address();
+    assert_eq!(s.bridge.get_challenge_period(), 24 * HOUR);
+
+    s.bridge.set_challenge_period(2 * HOUR);
+    assert_eq!(s.bridge.get_challenge_period(), 2 * HOUR);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        ChallengePeriodUpdated { period: 2 * HOUR }
+    ));
+    let limits = TokenLimits {
+        window_cap: U256::from(15_000),
+        window_duration: 24 * HOUR,
+        large_transfer_threshold: U256::from(10_000),
+        delay: HOUR,
+        ..Default::default()
+    };
+    s.bridge.set_limits(token, limits);
+
+    // Large releases wait out the challenge period, over-cap ones the delay
+    s.env.set_caller(s.operator);
+    let start = s.env.block_time();
+    let large = s.payload(1, U256::from(10_000));
+    s.bridge.receive_from_bridge(large.clone());
+    s.bridge.receive_from_bridge(s.payload(2, U256::from(9_000)));
+    let over_cap = s.payload(3, U256::from(9_000));
+    s.bridge.receive_from_bridge(over_cap.clone());
+    let unlock_at = |id| s.bridge.get_pending_release(id).unwrap().unlock_at;
+    assert_eq!(unlock_at(large.transfer_id), start + 2 * HOUR);
+    assert_eq!(unlock_at(over_cap.transfer_id), start + HOUR);
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.bridge.try_set_challenge_period(HOUR),
+        Err(Error::Unauthorized.into())
+    );
+}
+
+#[test]
+fn challenged_releases_wait_for_admin_review() {
+    let mut s = bridge_setup();
+    let (user, guardian) = (s.user, s.env.get_account(6));
+    let amount = U256::from(10_000);
+    hold_large_releases(&mut s);
+    s.bridge.grant_role(Role::Guardian, guardian);
+
+    s.env.set_caller(s.operator);
+    let payload = s.payload(1, amount);
+    s.bridge.receive_from_bridge(payload.clone());
+    let transfer_id = payload.transfer_id;
+    let pending = s.bridge.get_pending_releases();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].transfer_id, transfer_id);
+    assert_eq!(pending[0].status, ReleaseStatus::Pending);
+
+    s.env.set_caller(s.env.get_account(5));
+    assert_eq!(s.bridge.try_challenge(transfer_id), Err(Error::NotChallenger.into()));
+
+    s.env.set_caller(guardian);
+    s.bridge.challenge(transfer_id);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        TransferChallenged { transfer_id, challenger: guardian }
+    ));
+    assert_eq!(s.bridge.try_challenge(transfer_id), Err(Error::ReleaseChallenged.into()));
+
+    // Frozen past the window until the admin approves
+    s.env.advance_block_time(24 * HOUR);
+    assert_eq!(s.bridge.try_finalize(transfer_id), Err(Error::ReleaseChallenged.into()));
+    assert_eq!(
+        s.bridge.try_resolve_challenge(transfer_id, true),
+        Err(Error::Unauthorized.into())
+    );
+
+    s.env.set_caller(s.admin);
+    s.bridge.resolve_challenge(transfer_id, true);
+    assert_eq!(
+        s.bridge.try_resolve_challenge(transfer_id, true),
+        Err(Error::NotChallenged.into())
+    );
+    s.bridge.finalize(transfer_id);
+    assert_eq!(s.token_a.balance_of(&user), amount);
+    assert!(s.bridge.get_pending_releases().is_empty());
+}
+
+#[test]
+fn rejected_releases_are_dropped_without_fees() {
+    let mut s = bridge_setup();
+    let (token, operator) = (s.token_a.address(), s.operator);
+    let watcher = s.env.get_account(5);
+    hold_large_releases(&mut s);
+    let config = FeeConfig { fee_bps: 100, ..Default::default() };
+    s.bridge
+        .set_fee_config(U256::from(SOURCE_CHAIN_ID), EVM_TOKEN_A, config);
+
+    // Bonding at least the operator minimum makes any account a watcher
+    bond_operator(&mut s);
+    s.token_b.mint(&watcher, &U256::from(MIN_BOND));
+    s.env.set_caller(watcher);
+    s.token_b.approve(&s.bridge.address(), &U256::from(MIN_BOND));
+    s.bridge.bond(U256::from(MIN_BOND));
+
+    s.env.set_caller(operator);
+    let payload = s.payload(1, U256::from(20_000));
+    s.bridge.receive_from_bridge(payload.clone());
+    s.env.set_caller(watcher);
+    s.bridge.challenge(payload.transfer_id);
+
+    s.env.set_caller(s.admin);
+    s.bridge.resolve_challenge(payload.transfer_id, false);
+    assert_eq!(s.bridge.get_pending_release(payload.transfer_id), None);
+    assert_eq!(s.bridge.get_operator_fees(operator, token), U256::zero());
+    assert_eq!(s.token_a.balance_of(&s.user), U256::zero());
+    assert!(s.bridge.is_processed(U256::from(SOURCE_CHAIN_ID), EVM_VAULT, U256::one()));
+
+    // Once the window has passed, a release can no longer be challenged
+    s.env.set_caller(operator);
+    let late = s.payload(2, U256::from(20_000));
+    s.bridge.receive_from_bridge(late.clone());
+    s.env.advance_block_time(24 * HOUR);
+    s.env.set_caller(watcher);
+    assert_eq!(
+        s.bridge.try_challenge(late.transfer_id),
+        Err(Error::ChallengeWindowClosed.into())
+    );
+    s.bridge.finalize(late.transfer_id);
+    assert_eq!(s.bridge.get_operator_fees(operator, token), U256::from(200));
+}
+
+#[test]
+fn route_accounting_tracks_flows_per_token() {
+    let mut s = bridge_setup();
+    let (user, operator, token) = (s.user, s.operator, s.token_a.address());
+    let config = FeeConfig { fee_bps: 100, ..Default::default() };
+    s.bridge
+        .set_fee_config(U256::from(SOURCE_CHAIN_ID), EVM_TOKEN_A, config);
+    hold_large_releases(&mut s);
+    assert_eq!(s.bridge.get_route_accounting(token), RouteAccounting::default());
+
+    s.env.set_caller(operator);
+    s.bridge.receive_from_bridge(s.payload(1, U256::from(5_000)));
+    // Pending releases only count once finalized
+    let pending = s.payload(2, U256::from(20_000));
+    s.bridge.receive_from_bridge(pending.clone());
+    assert_eq!(
+        s.bridge.get_route_accounting(token),
+        RouteAccounting {
+            released: U256::from(4_950),
+            fees: U256::from(50),
+            bridged_out: U256::zero(),
+        }
+    );
+
+    s.env.advance_block_time(24 * HOUR);
+    s.bridge.finalize(pending.transfer_id);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        BridgeRelease {
+            recipient: user,
+            amount: U256::from(19_800),
+            fee: U256::from(200),
+            nonce: U256::from(2),
+            token,
+            source_tx_hash: source_tx_hash(2),
+        }
+    ));
+
+    s.env.set_caller(user);
+    s.token_a.approve(&s.bridge.address(), &U256::from(3_000));
+    s.bridge
+        .bridge_out(token, U256::from(3_000), [0xabu8; 20], U256::from(SOURCE_CHAIN_ID));
+    assert_eq!(
+        s.bridge.get_route_accounting(token),
+        RouteAccounting {
+            released: U256::from(24_750),
+            fees: U256::from(250),
+            bridged_out: U256::from(3_000),
+        }
+    );
+    assert_eq!(
+        s.bridge.get_route_accounting(s.token_b.address()),
+        RouteAccounting::default()
+    );
+}
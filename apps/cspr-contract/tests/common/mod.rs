@@ -0,0 +1,170 @@
+//! Shared fixtures for the integration tests.
+#![allow(dead_code)]
+
+use cspr_contract::access::Role;
+use cspr_contract::bridge::{
+    AnchoreBridge, AnchoreBridgeHostRef, AnchoreBridgeInitArgs, ReleasePayload, TokenMode,
+};
+use cspr_contract::mock_token::{MockToken, MockTokenHostRef, MockTokenInitArgs};
+use cspr_contract::operator_registry::{OperatorRegistry, OperatorRegistryHostRef};
+use cspr_contract::pool::{AnchorePool, AnchorePoolHostRef, AnchorePoolInitArgs};
+use odra::casper_types::bytesrepr::{Bytes, ToBytes};
+use odra::casper_types::U256;
+use odra::host::{Deployer, HostEnv, NoArgs};
+use odra::prelude::*;
+
+pub const INITIAL_BALANCE: u64 = 1_000_000_000;
+pub const SEED_LIQUIDITY: u64 = 100_000_000;
+
+pub struct PoolSetup {
+    pub env: HostEnv,
+    pub token_a: MockTokenHostRef,
+    pub token_b: MockTokenHostRef,
+    pub pool: AnchorePoolHostRef,
+}
+
+pub fn deploy_token(env: &HostEnv, symbol: &str) -> MockTokenHostRef {
+    MockToken::deploy(
+        env,
+        MockTokenInitArgs {
+            name: symbol.to_string(),
+            symbol: symbol.to_string(),
+            decimals: 6,
+            initial_supply: U256::zero(),
+        },
+    )
+}
+
+/// Deploys two tokens and a pool, funds the first two accounts and approves the pool.
+pub fn pool_setup() -> PoolSetup {
+    let env = odra_test::env();
+    let mut token_a = deploy_token(&env, "TKA");
+    let mut token_b = deploy_token(&env, "TKB");
+    let pool = AnchorePool::deploy(
+        &env,
+        AnchorePoolInitArgs {
+            token_a: token_a.address(),
+            token_b: token_b.address(),
+        },
+    );
+
+    // The deployer (account 0) holds MINTER on both tokens
+    for i in 0..2 {
+        let account = env.get_account(i);
+        for token in [&mut token_a, &mut token_b] {
+            env.set_caller(env.get_account(0));
+            token.mint(&account, &U256::from(INITIAL_BALANCE));
+            env.set_caller(account);
+            token.approve(&pool.address(), &U256::from(INITIAL_BALANCE));
+        }
+    }
+    env.set_caller(env.get_account(0));
+
+    PoolSetup { env, token_a, token_b, pool }
+}
+
+/// Same as `pool_setup`, with `SEED_LIQUIDITY` of each token deposited by account 0.
+pub fn seeded_pool() -> PoolSetup {
+    let mut s = pool_setup();
+    s.pool
+        .add_liquidity(U256::from(SEED_LIQUIDITY), U256::from(SEED_LIQUIDITY));
+    s
+}
+
+pub const BRIDGE_FUNDING: u64 = 50_000_000;
+pub const SOURCE_CHAIN_ID: u64 = 11_155_111;
+pub const EVM_TOKEN_A: [u8; 20] = [0x11; 20];
+pub const EVM_VAULT: [u8; 20] = [0xee; 20];
+
+pub struct BridgeSetup {
+    pub env: HostEnv,
+    pub token_a: MockTokenHostRef,
+    pub token_b: MockTokenHostRef,
+    pub pool: AnchorePoolHostRef,
+    pub bridge: AnchoreBridgeHostRef,
+    pub registry: OperatorRegistryHostRef,
+    pub admin: Address,
+    pub operator: Address,
+    pub user: Address,
+}
+
+impl BridgeSetup {
+    /// Registers `account` as an operator, with its test key
+    pub fn add_operator(&mut self, account: Address) {
+        add_operator(&self.env, &mut self.registry, account);
+    }
+
+    /// Moves the bridge to a new epoch of `operators`, approved by the admin
+    pub fn rotate(&mut self, operators: Vec<Address>, threshold: u32) {
+        self.env.set_caller(self.admin);
+        self.bridge.propose_operator_set(operators, threshold);
+        self.bridge.approve_operator_set();
+    }
+
+    /// Release of `amount` EVM_TOKEN_A from EVM_VAULT to the user, identified
+    /// by `nonce`, in the bridge's current epoch
+    pub fn payload(&self, nonce: u64, amount: U256) -> ReleasePayload {
+        let mut transfer_id = [0u8; 32];
+        transfer_id[24..].copy_from_slice(&nonce.to_be_bytes());
+        ReleasePayload {
+            transfer_id,
+            source_tx_hash: source_tx_hash(nonce),
+            source_chain_id: U256::from(SOURCE_CHAIN_ID),
+            source_contract: EVM_VAULT,
+            evm_token: EVM_TOKEN_A,
+            recipient: recipient(&self.env, self.user),
+            amount,
+            nonce: U256::from(nonce),
+            target_token: None,
+            min_amount_out: U256::zero(),
+            epoch: self.bridge.get_epoch(),
+        }
+    }
+}
+
+/// Seeded pool plus a bridge holding `BRIDGE_FUNDING` of token_a, registered as
+/// LockUnlock and routed from EVM_TOKEN_A on SOURCE_CHAIN_ID with equal decimals.
+/// Account 0 is the admin, account 2 the operator, account 3 a plain user.
+/// The bridge holds OPERATOR_MANAGER on the registry so it can slash.
+pub fn bridge_setup() -> BridgeSetup {
+    let PoolSetup { env, mut token_a, token_b, pool } = seeded_pool();
+    let admin = env.get_account(0);
+    let operator = env.get_account(2);
+    let user = env.get_account(3);
+
+    let mut registry = OperatorRegistry::deploy(&env, NoArgs);
+    add_operator(&env, &mut registry, operator);
+    let mut bridge = AnchoreBridge::deploy(
+        &env,
+        AnchoreBridgeInitArgs {
+            pool: pool.address(),
+            operator_registry: registry.address(),
+        },
+    );
+    registry.grant_role(Role::OperatorManager, bridge.address());
+    bridge.set_token_mode(token_a.address(), TokenMode::LockUnlock);
+    bridge.set_route(U256::from(SOURCE_CHAIN_ID), EVM_TOKEN_A, token_a.address(), 6, 6);
+    token_a.mint(&bridge.address(), &U256::from(BRIDGE_FUNDING));
+
+    BridgeSetup { env, token_a, token_b, pool, bridge, registry, admin, operator, user }
+}
+
+/// `account`'s public key, encoded as a release recipient
+pub fn recipient(env: &HostEnv, account: Address) -> Bytes {
+    Bytes::from(env.public_key(&account).to_bytes().unwrap())
+}
+
+/// Hash of the EVM transaction the fixture payload for `nonce` came from
+pub fn source_tx_hash(nonce: u64) -> [u8; 32] {
+    let mut tx_hash = [0xab; 32];
+    tx_hash[24..].copy_from_slice(&nonce.to_be_bytes());
+    tx_hash
+}
+
+pub fn add_operator(env: &HostEnv, registry: &mut OperatorRegistryHostRef, account: Address) {
+    registry.set_operator(
+        env.public_key(&account),
+        "operator".to_string(),
+        "https://operator.example".to_string(),
+    );
+}
@@ -0,0 +1,105 @@
+//! Per-entrypoint gas benchmark for `AnchorePool`.
+//!
+//! Reserves are tracked from transfer amounts, so entrypoints no longer end
+//! with two `balance_of` cross-contract calls. `MockPoolBench` replays a
+//! swap's reserve bookkeeping both ways, which measures what each entrypoint
+//! saves.
+//!
+//! Storage is packed into a `PoolConfig` and a `PoolState` slot, so each
+//! entrypoint does two reads and one state write instead of up to nine `Var`
+//! accesses. `MockPoolBench` replays the same swap in both layouts.
+//!
+//! Gas is only metered on the Casper backend, so the benchmark is ignored by
+//! default: `cargo odra test -b casper -- --ignored --nocapture gas`
+
+mod common;
+
+use common::seeded_pool;
+use cspr_contract::mock_pool_bench::{MockPoolBench, MockPoolBenchInitArgs};
+use odra::casper_types::{U256, U512};
+use odra::host::{Deployer, HostEnv};
+use odra::prelude::*;
+use odra::DeployReport;
+
+/// Gas of the last contract call, from the backend's gas report
+fn last_call_gas(env: &HostEnv) -> U512 {
+    match env.gas_report().into_iter().last() {
+        Some(DeployReport::ContractCall { gas, .. }) => gas,
+        _ => U512::zero(),
+    }
+}
+
+#[test]
+#[ignore = "gas is only metered on the Casper backend"]
+fn gas_per_entrypoint() {
+    let mut s = seeded_pool();
+    let me = s.env.get_account(0);
+    let token_a = s.token_a.address();
+
+    let mut bench = MockPoolBench::deploy(
+        &s.env,
+        MockPoolBenchInitArgs {
+            token_a,
+            token_b: s.token_b.address(),
+        },
+    );
+    bench.swap_reading_balances(U256::from(1_000));
+    let reading_balances = last_call_gas(&s.env);
+    bench.swap_tracking_reserves(U256::from(1_000));
+    let tracking_reserves = last_call_gas(&s.env);
+    assert!(!tracking_reserves.is_zero(), "gas should be metered");
+    assert!(
+        tracking_reserves < reading_balances,
+        "tracking reserves should be cheaper than reading balances"
+    );
+
+    let mut report: Vec<(&str, U512)> = Vec::new();
+    s.pool.add_liquidity(U256::from(1_000_000), U256::from(1_000_000));
+    report.push(("add_liquidity", last_call_gas(&s.env)));
+    s.pool.remove_liquidity(U256::from(1_000));
+    report.push(("remove_liquidity", last_call_gas(&s.env)));
+    s.pool
+        .swap_exact_tokens_in(U256::from(1_000), token_a, U256::zero(), me);
+    report.push(("swap_exact_tokens_in", last_call_gas(&s.env)));
+    s.pool
+        .swap_tokens_for_exact_out(U256::from(1_000), token_a, U256::MAX, me);
+    report.push(("swap_tokens_for_exact_out", last_call_gas(&s.env)));
+    s.pool.zap_in(token_a, U256::from(1_000_000), U256::zero(), me);
+    report.push(("zap_in", last_call_gas(&s.env)));
+    s.pool.zap_out(U256::from(1_000), token_a, U256::zero());
+    report.push(("zap_out", last_call_gas(&s.env)));
+
+    println!("{:<28}{:>16}", "entrypoint", "gas");
+    for (name, gas) in &report {
+        println!("{:<28}{:>16}", name, gas);
+    }
+    println!(
+        "{:<28}{:>16}",
+        "saved per entrypoint",
+        reading_balances - tracking_reserves
+    );
+
+}
+
+#[test]
+#[ignore = "gas is only metered on the Casper backend"]
+fn packed_storage_is_cheaper_than_separate_vars() {
+    let s = seeded_pool();
+    let mut bench = MockPoolBench::deploy(
+        &s.env,
+        MockPoolBenchInitArgs {
+            token_a: s.token_a.address(),
+            token_b: s.token_b.address(),
+        },
+    );
+    bench.swap_tracking_reserves(U256::from(1_000));
+    let separate_vars = last_call_gas(&s.env);
+    bench.swap_packed(U256::from(1_000));
+    let packed = last_call_gas(&s.env);
+    assert!(!packed.is_zero(), "gas should be metered");
+
+    println!("{:<28}{:>16}", "layout", "swap gas");
+    println!("{:<28}{:>16}", "separate vars (before)", separate_vars);
+    println!("{:<28}{:>16}", "packed (after)", packed);
+    assert!(packed < separate_vars, "the packed layout should be cheaper");
+}
@@ -0,0 +1,194 @@
+//! Integration tests for bridge liquidity providers, through `AnchoreBridge`.
+
+mod common;
+
+use common::{bridge_setup, BridgeSetup, BRIDGE_FUNDING, EVM_TOKEN_A, SOURCE_CHAIN_ID};
+use cspr_contract::bridge::{Error, FeeConfig, TokenLimits};
+use cspr_contract::events::{BridgeLiquidityAdded, BridgeLiquidityRemoved};
+use cspr_contract::liquidity::Error as LiquidityError;
+use odra::casper_types::U256;
+use odra::prelude::*;
+
+const HOUR: u64 = 60 * 60 * 1000;
+
+/// Deposits `amount` of token_a from `provider`
+fn add_liquidity(s: &mut BridgeSetup, provider: Address, amount: u64) {
+    s.env.set_caller(provider);
+    s.token_a.approve(&s.bridge.address(), &U256::from(amount));
+    s.bridge.add_liquidity(s.token_a.address(), U256::from(amount));
+}
+
+#[test]
+fn providers_share_their_deposits() {
+    let mut s = bridge_setup();
+    let (token, provider, late) = (s.token_a.address(), s.env.get_account(1), s.admin);
+    // The fixture's pre-funding belongs to no provider
+    assert_eq!(s.bridge.get_liquidity(token), U256::zero());
+
+    // A first deposit can't capture the custody
+    let balance = s.token_a.balance_of(&provider);
+    add_liquidity(&mut s, provider, 1);
+    s.bridge.remove_liquidity(token, U256::one());
+    assert_eq!(s.token_a.balance_of(&provider), balance);
+    assert_eq!(s.token_a.balance_of(&s.bridge.address()), U256::from(BRIDGE_FUNDING));
+
+    add_liquidity(&mut s, provider, 50_000_000);
+    assert_eq!(s.bridge.get_liquidity_shares(token, provider), U256::from(50_000_000));
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        BridgeLiquidityAdded {
+            provider,
+            token,
+            amount: U256::from(50_000_000),
+            shares: U256::from(50_000_000),
+        }
+    ));
+
+    add_liquidity(&mut s, late, 10_000_000);
+    assert_eq!(s.bridge.get_liquidity_shares(token, late), U256::from(10_000_000));
+    assert_eq!(s.bridge.get_total_liquidity_shares(token), U256::from(60_000_000));
+
+    // Releases are paid from the pre-funding without changing what providers own
+    s.env.set_caller(s.operator);
+    s.bridge.receive_from_bridge(s.payload(1, U256::from(11_000_000)));
+    assert_eq!(s.bridge.get_liquidity(token), U256::from(60_000_000));
+
+    s.env.set_caller(provider);
+    assert_eq!(
+        s.bridge.try_remove_liquidity(token, U256::from(50_000_001)),
+        Err(LiquidityError::InsufficientShares.into())
+    );
+    assert_eq!(
+        s.bridge.try_remove_liquidity(token, U256::zero()),
+        Err(LiquidityError::ZeroAmount.into())
+    );
+    let balance = s.token_a.balance_of(&provider);
+    s.bridge.remove_liquidity(token, U256::from(25_000_000));
+    assert_eq!(s.token_a.balance_of(&provider), balance + U256::from(25_000_000));
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        BridgeLiquidityRemoved {
+            provider,
+            token,
+            amount: U256::from(25_000_000),
+            shares: U256::from(25_000_000),
+        }
+    ));
+    assert_eq!(s.bridge.get_liquidity(token), U256::from(35_000_000));
+}
+
+#[test]
+fn releases_past_the_other_custody_cost_providers() {
+    let mut s = bridge_setup();
+    let (token, provider, late) = (s.token_a.address(), s.env.get_account(1), s.admin);
+    add_liquidity(&mut s, provider, 50_000_000);
+
+    // The pre-funding covers the first BRIDGE_FUNDING, the deposit the rest
+    s.env.set_caller(s.operator);
+    s.bridge.receive_from_bridge(s.payload(1, U256::from(60_000_000)));
+    assert_eq!(s.bridge.get_liquidity(token), U256::from(40_000_000));
+
+    // Nothing is left to pay a release beyond the providers' value
+    assert_eq!(
+        s.bridge.try_receive_from_bridge(s.payload(2, U256::from(40_000_001))),
+        Err(LiquidityError::InsufficientLiquidity.into())
+    );
+
+    // Later deposits buy in at the lower share value
+    add_liquidity(&mut s, late, 8_000_000);
+    assert_eq!(s.bridge.get_liquidity_shares(token, late), U256::from(10_000_000));
+
+    s.env.set_caller(provider);
+    let balance = s.token_a.balance_of(&provider);
+    s.bridge.remove_liquidity(token, U256::from(50_000_000));
+    assert_eq!(s.token_a.balance_of(&provider), balance + U256::from(40_000_000));
+    assert_eq!(s.bridge.get_liquidity(token), U256::from(8_000_000));
+}
+
+#[test]
+fn only_lock_unlock_tokens_take_liquidity() {
+    let mut s = bridge_setup();
+    let token_b = s.token_b.address();
+    assert_eq!(
+        s.bridge.try_add_liquidity(token_b, U256::from(1_000)),
+        Err(Error::UnsupportedToken.into())
+    );
+}
+
+#[test]
+fn pending_releases_are_not_withdrawable() {
+    let mut s = bridge_setup();
+    let (token, provider) = (s.token_a.address(), s.env.get_account(1));
+    add_liquidity(&mut s, provider, 50_000_000);
+
+    // Releases draw the custody down to the deposit, then one is held
+    s.env.set_caller(s.operator);
+    s.bridge.receive_from_bridge(s.payload(1, U256::from(BRIDGE_FUNDING)));
+    let limits = TokenLimits {
+        large_transfer_threshold: U256::from(10_000),
+        ..Default::default()
+    };
+    s.env.set_caller(s.admin);
+    s.bridge.set_limits(token, limits);
+    s.bridge.set_challenge_period(HOUR);
+    let payload = s.payload(2, U256::from(20_000));
+    s.env.set_caller(s.operator);
+    s.bridge.receive_from_bridge(payload.clone());
+    assert_eq!(s.bridge.get_liquidity(token), U256::from(50_000_000));
+
+    // The provider can take everything but the pending release
+    s.env.set_caller(provider);
+    assert_eq!(
+        s.bridge.try_remove_liquidity(token, U256::from(50_000_000)),
+        Err(LiquidityError::InsufficientLiquidity.into())
+    );
+    let balance = s.token_a.balance_of(&provider);
+    s.bridge.remove_liquidity(token, U256::from(49_980_000));
+    assert_eq!(s.token_a.balance_of(&provider), balance + U256::from(49_980_000));
+
+    s.env.advance_block_time(HOUR);
+    s.bridge.finalize(payload.transfer_id);
+    assert_eq!(
+        s.token_a.balance_of(&s.user),
+        U256::from(BRIDGE_FUNDING + 20_000)
+    );
+    // Only the deposit was left to pay it from
+    assert_eq!(s.bridge.get_liquidity(token), U256::zero());
+}
+
+#[test]
+fn providers_earn_their_fee_share() {
+    let mut s = bridge_setup();
+    let (token, provider, operator) = (s.token_a.address(), s.env.get_account(1), s.operator);
+    let config = FeeConfig {
+        fee_bps: 100,
+        treasury_share_bps: 2_000,
+        lp_share_bps: 5_000,
+        ..Default::default()
+    };
+    s.bridge
+        .set_fee_config(U256::from(SOURCE_CHAIN_ID), EVM_TOKEN_A, config);
+
+    // Without providers, their half of the 10_000 fee goes to the treasury
+    s.env.set_caller(operator);
+    s.bridge.receive_from_bridge(s.payload(1, U256::from(1_000_000)));
+    assert_eq!(s.bridge.get_treasury_fees(token), U256::from(7_000));
+    assert_eq!(s.bridge.get_operator_fees(operator, token), U256::from(3_000));
+    assert_eq!(s.bridge.get_liquidity(token), U256::zero());
+
+    add_liquidity(&mut s, provider, 49_000_000);
+    s.env.set_caller(operator);
+    s.bridge.receive_from_bridge(s.payload(2, U256::from(1_000_000)));
+    assert_eq!(s.bridge.get_treasury_fees(token), U256::from(9_000));
+    assert_eq!(s.bridge.get_operator_fees(operator, token), U256::from(6_000));
+    assert_eq!(s.bridge.get_liquidity(token), U256::from(49_005_000));
+
+    // Claims pay out reserved fees without touching the liquidity
+    s.bridge.claim_operator_fees(token);
+    assert_eq!(s.bridge.get_liquidity(token), U256::from(49_005_000));
+
+    s.env.set_caller(provider);
+    let balance = s.token_a.balance_of(&provider);
+    s.bridge.remove_liquidity(token, U256::from(49_000_000));
+    assert_eq!(s.token_a.balance_of(&provider), balance + U256::from(49_005_000));
+}
@@ -0,0 +1,197 @@
+//! Integration tests for cross-chain messages delivered through `AnchoreBridge`.
+
+mod common;
+
+use common::{bridge_setup, BridgeSetup, SOURCE_CHAIN_ID};
+use cspr_contract::bridge::{Error, MessageStatus};
+use cspr_contract::events::{MessageExecuted, MessageReceived};
+use cspr_contract::mock_message_target::{
+    Error as TargetError, MockMessageTarget, MockMessageTargetHostRef, MockMessageTargetInitArgs,
+};
+use odra::casper_types::bytesrepr::{Bytes, ToBytes};
+use odra::casper_types::{runtime_args, U256};
+use odra::host::Deployer;
+use odra::prelude::OdraResult;
+use odra::prelude::*;
+
+const EVM_SENDER: [u8; 20] = [0x5e; 20];
+
+/// Deploys a message target for the bridge and allowlists its `store`
+fn deploy_target(s: &mut BridgeSetup) -> MockMessageTargetHostRef {
+    let target = MockMessageTarget::deploy(
+        &s.env,
+        MockMessageTargetInitArgs { bridge: s.bridge.address() },
+    );
+    s.bridge
+        .set_message_target(target.address(), "store".to_string(), true);
+    target
+}
+
+/// Args of `MockMessageTarget::store`, as sent from EVM
+fn store_args(value: u64) -> Bytes {
+    Bytes::from(runtime_args! { "value" => U256::from(value) }.to_bytes().unwrap())
+}
+
+/// Attests a `store` message from EVM_SENDER as the current caller
+fn receive_store(
+    s: &mut BridgeSetup,
+    target: Address,
+    nonce: u64,
+    args: Bytes,
+) -> OdraResult<()> {
+    let chain_id = U256::from(SOURCE_CHAIN_ID);
+    let (nonce, entry_point) = (U256::from(nonce), "store".to_string());
+    s.bridge
+        .try_receive_message(chain_id, EVM_SENDER, nonce, target, entry_point, args)
+}
+
+#[test]
+fn attested_messages_are_delivered_then_executed() {
+    let mut s = bridge_setup();
+    let target = deploy_target(&mut s);
+    let (chain_id, second) = (U256::from(SOURCE_CHAIN_ID), s.env.get_account(4));
+
+    s.env.set_caller(s.user);
+    assert_eq!(
+        receive_store(&mut s, target.address(), 1, store_args(42)),
+        Err(Error::NotOperator.into())
+    );
+
+    s.env.set_caller(s.admin);
+    s.add_operator(second);
+    s.rotate(vec![s.operator, second], 2);
+    s.env.set_caller(s.operator);
+    receive_store(&mut s, target.address(), 1, store_args(42)).unwrap();
+    assert_eq!(s.bridge.get_message(chain_id, EVM_SENDER, U256::one()), None);
+    assert_eq!(
+        s.bridge.try_execute_message(chain_id, EVM_SENDER, U256::one()),
+        Err(Error::UnknownMessage.into())
+    );
+
+    s.env.set_caller(second);
+    receive_store(&mut s, target.address(), 1, store_args(42)).unwrap();
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        MessageReceived {
+            source_chain_id: chain_id,
+            sender: EVM_SENDER,
+            nonce: U256::one(),
+            target: target.address(),
+            entry_point: "store".to_string(),
+        }
+    ));
+    assert_eq!(
+        s.bridge
+            .get_message(chain_id, EVM_SENDER, U256::one())
+            .map(|delivered| (delivered.status, delivered.attempts)),
+        Some((MessageStatus::Delivered, 0))
+    );
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        receive_store(&mut s, target.address(), 1, store_args(42)),
+        Err(Error::NonceAlreadyProcessed.into())
+    );
+
+    // Anyone executes; the target sees the EVM sender
+    s.env.set_caller(s.user);
+    s.bridge.execute_message(chain_id, EVM_SENDER, U256::one());
+    assert_eq!(target.get_last_value(), U256::from(42));
+    assert_eq!(target.get_last_sender(), Some(EVM_SENDER));
+    assert_eq!(target.get_last_source_chain_id(), chain_id);
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        MessageExecuted {
+            source_chain_id: chain_id,
+            sender: EVM_SENDER,
+            nonce: U256::one(),
+            success: true,
+            attempt: 1,
+        }
+    ));
+    assert_eq!(
+        s.bridge.try_execute_message(chain_id, EVM_SENDER, U256::one()),
+        Err(Error::MessageAlreadyExecuted.into())
+    );
+}
+
+#[test]
+fn only_allowlisted_targets_receive_messages() {
+    let mut s = bridge_setup();
+    let mut target = deploy_target(&mut s);
+    let (chain_id, pool) = (U256::from(SOURCE_CHAIN_ID), s.pool.address());
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        receive_store(&mut s, pool, 1, store_args(1)),
+        Err(Error::TargetNotAllowed.into())
+    );
+    assert_eq!(
+        s.bridge.try_set_message_target(pool, "store".to_string(), true),
+        Err(Error::Unauthorized.into())
+    );
+
+    // Other entry points of an allowlisted target stay closed
+    let set_accepting = "set_accepting".to_string();
+    assert!(!s.bridge.is_message_target(target.address(), set_accepting.clone()));
+    assert_eq!(
+        s.bridge.try_receive_message(
+            chain_id,
+            EVM_SENDER,
+            U256::one(),
+            target.address(),
+            set_accepting,
+            store_args(1),
+        ),
+        Err(Error::TargetNotAllowed.into())
+    );
+
+    // Targets only take calls from the bridge
+    assert_eq!(
+        target.try_store(chain_id, EVM_SENDER, U256::one()),
+        Err(TargetError::NotBridge.into())
+    );
+}
+
+#[test]
+fn failed_messages_can_be_retried() {
+    let mut s = bridge_setup();
+    let mut target = deploy_target(&mut s);
+    let chain_id = U256::from(SOURCE_CHAIN_ID);
+    target.set_accepting(false);
+
+    s.env.set_caller(s.operator);
+    receive_store(&mut s, target.address(), 1, store_args(7)).unwrap();
+    // A declined call is recorded without undoing the delivery
+    s.bridge.execute_message(chain_id, EVM_SENDER, U256::one());
+    assert!(s.env.emitted_event(
+        &s.bridge.address(),
+        MessageExecuted {
+            source_chain_id: chain_id,
+            sender: EVM_SENDER,
+            nonce: U256::one(),
+            success: false,
+            attempt: 1,
+        }
+    ));
+    assert_eq!(target.get_last_value(), U256::zero());
+
+    target.set_accepting(true);
+    s.bridge.execute_message(chain_id, EVM_SENDER, U256::one());
+    assert_eq!(target.get_last_value(), U256::from(7));
+    assert_eq!(
+        s.bridge
+            .get_message(chain_id, EVM_SENDER, U256::one())
+            .map(|delivered| (delivered.status, delivered.attempts)),
+        Some((MessageStatus::Executed, 2))
+    );
+
+    // Args that don't decode fail every attempt instead of reverting
+    receive_store(&mut s, target.address(), 2, Bytes::from(vec![1, 2, 3])).unwrap();
+    s.bridge.execute_message(chain_id, EVM_SENDER, U256::from(2));
+    assert_eq!(
+        s.bridge
+            .get_message(chain_id, EVM_SENDER, U256::from(2))
+            .map(|delivered| delivered.status),
+        Some(MessageStatus::Failed)
+    );
+}
@@ -0,0 +1,83 @@
+//! Integration tests for `OperatorRegistry`.
+
+mod common;
+
+use common::bridge_setup;
+use cspr_contract::events::{OperatorInfoUpdated, OperatorUpdated};
+use cspr_contract::operator_registry::{Error, OperatorInfo};
+use odra::prelude::*;
+
+#[test]
+fn manager_registers_and_removes_operators() {
+    let mut s = bridge_setup();
+    let candidate = s.env.get_account(4);
+    assert_eq!(s.registry.get_operators(), vec![s.operator]);
+
+    let public_key = s.env.public_key(&candidate);
+    s.registry.set_operator(
+        public_key.clone(),
+        "node-4".to_string(),
+        "https://node-4.example".to_string(),
+    );
+    assert!(s.registry.is_operator(candidate));
+    assert_eq!(s.registry.operator_count(), 2);
+    assert_eq!(s.registry.get_public_key(candidate), Some(public_key.clone()));
+    assert!(s.env.emitted_event(
+        &s.registry.address(),
+        OperatorUpdated { operator: candidate, is_active: true }
+    ));
+
+    // Re-registering only updates the metadata
+    s.registry.set_operator(
+        public_key.clone(),
+        "node-4b".to_string(),
+        "https://node-4b.example".to_string(),
+    );
+    assert_eq!(s.registry.operator_count(), 2);
+    assert_eq!(
+        s.registry.get_operator(candidate),
+        Some(OperatorInfo {
+            public_key,
+            name: "node-4b".to_string(),
+            endpoint: "https://node-4b.example".to_string(),
+        })
+    );
+    assert!(s.env.emitted_event(
+        &s.registry.address(),
+        OperatorInfoUpdated {
+            operator: candidate,
+            name: "node-4b".to_string(),
+            endpoint: "https://node-4b.example".to_string(),
+        }
+    ));
+
+    s.registry.remove_operator(candidate);
+    assert!(!s.registry.is_operator(candidate));
+    assert_eq!(s.registry.get_operators(), vec![s.operator]);
+    assert_eq!(s.registry.get_operator(candidate), None);
+    assert!(s.env.emitted_event(
+        &s.registry.address(),
+        OperatorUpdated { operator: candidate, is_active: false }
+    ));
+    assert_eq!(
+        s.registry.try_remove_operator(candidate),
+        Err(Error::NotRegistered.into())
+    );
+}
+
+#[test]
+fn only_operator_managers_change_the_registry() {
+    let mut s = bridge_setup();
+    let public_key = s.env.public_key(&s.user);
+
+    s.env.set_caller(s.operator);
+    assert_eq!(
+        s.registry
+            .try_set_operator(public_key, "node".to_string(), "https://node.example".to_string()),
+        Err(Error::Unauthorized.into())
+    );
+    assert_eq!(
+        s.registry.try_remove_operator(s.operator),
+        Err(Error::Unauthorized.into())
+    );
+}
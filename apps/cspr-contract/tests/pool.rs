@@ -0,0 +1,440 @@
+//! Integration tests for `AnchorePool`.
+
+mod common;
+
+use common::{deploy_token, pool_setup, seeded_pool, SEED_LIQUIDITY};
+use cspr_contract::events::Swap;
+use cspr_contract::pool::{AnchorePool, AnchorePoolInitArgs, Error};
+use odra::casper_types::account::AccountHash;
+use odra::casper_types::U256;
+use odra::host::Deployer;
+use odra::prelude::*;
+
+#[test]
+fn init_rejects_identical_tokens() {
+    let env = odra_test::env();
+    let token = deploy_token(&env, "TKA");
+    let result = AnchorePool::try_deploy(
+        &env,
+        AnchorePoolInitArgs {
+            token_a: token.address(),
+            token_b: token.address(),
+        },
+    );
+    assert_eq!(result.err(), Some(Error::IdenticalTokens.into()));
+}
+
+#[test]
+fn init_rejects_zero_address() {
+    let env = odra_test::env();
+    let token = deploy_token(&env, "TKA");
+    let result = AnchorePool::try_deploy(
+        &env,
+        AnchorePoolInitArgs {
+            token_a: token.address(),
+            token_b: Address::Account(AccountHash::new([0u8; 32])),
+        },
+    );
+    assert_eq!(result.err(), Some(Error::ZeroAddress.into()));
+}
+
+#[test]
+fn init_stores_pair_in_canonical_order() {
+    let env = odra_test::env();
+    let first = deploy_token(&env, "TKA").address();
+    let second = deploy_token(&env, "TKB").address();
+
+    let forward = AnchorePool::deploy(
+        &env,
+        AnchorePoolInitArgs { token_a: first, token_b: second },
+    );
+    let reverse = AnchorePool::deploy(
+        &env,
+        AnchorePoolInitArgs { token_a: second, token_b: first },
+    );
+
+    let (token_a, token_b) = forward.get_tokens();
+    assert!(token_a < token_b);
+    assert_eq!(forward.get_tokens(), reverse.get_tokens());
+}
+
+#[test]
+fn views_reject_unknown_token() {
+    let s = seeded_pool();
+    let unknown = s.pool.address();
+    assert_eq!(s.pool.try_get_price(unknown), Err(Error::InvalidInputToken.into()));
+    assert_eq!(
+        s.pool.try_get_amount_out(U256::from(1_000), unknown),
+        Err(Error::InvalidInputToken.into())
+    );
+    assert_eq!(
+        s.pool.try_get_amount_in(U256::from(1_000), unknown),
+        Err(Error::InvalidInputToken.into())
+    );
+    assert_eq!(
+        s.pool.try_get_zap_swap_amount(U256::from(1_000), unknown),
+        Err(Error::InvalidInputToken.into())
+    );
+}
+
+#[test]
+fn add_liquidity_rejects_zero_amount() {
+    let mut s = pool_setup();
+    assert_eq!(
+        s.pool.try_add_liquidity(U256::zero(), U256::from(1_000)),
+        Err(Error::ZeroAmount.into())
+    );
+}
+
+#[test]
+fn add_liquidity_rejects_dust_mint() {
+    let mut s = pool_setup();
+    s.pool.add_liquidity(U256::from(SEED_LIQUIDITY), U256::one());
+    // 1 * supply / reserve_a rounds down to zero LP tokens
+    assert_eq!(
+        s.pool.try_add_liquidity(U256::one(), U256::one()),
+        Err(Error::InsufficientLiquidity.into())
+    );
+}
+
+#[test]
+fn remove_liquidity_error_paths() {
+    let mut s = seeded_pool();
+    assert_eq!(
+        s.pool.try_remove_liquidity(U256::zero()),
+        Err(Error::ZeroLiquidity.into())
+    );
+
+    let lp = s.pool.get_lp_balance(s.env.get_account(0));
+    assert_eq!(
+        s.pool.try_remove_liquidity(lp + U256::one()),
+        Err(Error::InsufficientLpBalance.into())
+    );
+
+    s.env.set_caller(s.env.get_account(1));
+    assert_eq!(
+        s.pool.try_remove_liquidity(U256::one()),
+        Err(Error::InsufficientLpBalance.into())
+    );
+}
+
+#[test]
+fn swap_error_paths() {
+    let mut s = pool_setup();
+    let me = s.env.get_account(0);
+    let token_a = s.token_a.address();
+
+    assert_eq!(
+        s.pool.try_swap_exact_tokens_in(U256::from(1_000), token_a, U256::zero(), me),
+        Err(Error::EmptyReserves.into())
+    );
+
+    s.pool
+        .add_liquidity(U256::from(SEED_LIQUIDITY), U256::from(SEED_LIQUIDITY));
+
+    assert_eq!(
+        s.pool.try_swap_exact_tokens_in(U256::zero(), token_a, U256::zero(), me),
+        Err(Error::ZeroAmount.into())
+    );
+    assert_eq!(
+        s.pool.try_swap_exact_tokens_in(U256::from(1_000), s.pool.address(), U256::zero(), me),
+        Err(Error::InvalidInputToken.into())
+    );
+    assert_eq!(
+        s.pool.try_swap_exact_tokens_in(U256::from(1_000), token_a, U256::from(1_000), me),
+        Err(Error::OutputBelowMinimum.into())
+    );
+    assert_eq!(
+        s.pool.try_swap_tokens_for_exact_out(U256::from(1_000), token_a, U256::from(1_000), me),
+        Err(Error::InputExceedsMaximum.into())
+    );
+    assert_eq!(
+        s.pool.try_swap_tokens_for_exact_out(U256::from(SEED_LIQUIDITY), token_a, U256::MAX, me),
+        Err(Error::InsufficientLiquidity.into())
+    );
+    assert_eq!(
+        s.pool.try_swap_tokens_for_exact_out(U256::zero(), token_a, U256::MAX, me),
+        Err(Error::ZeroAmount.into())
+    );
+}
+
+#[test]
+fn swap_exact_tokens_in_follows_constant_product() {
+    let mut s = seeded_pool();
+    let me = s.env.get_account(0);
+    let amount_in = U256::from(1_000_000);
+    let quoted = s.pool.get_amount_out(amount_in, s.token_a.address());
+
+    // 1_000_000 * 9997 * 1e8 / (1e8 * 10000 + 1_000_000 * 9997)
+    assert_eq!(quoted, U256::from(989_804));
+
+    let before = s.token_b.balance_of(&me);
+    s.pool
+        .swap_exact_tokens_in(amount_in, s.token_a.address(), quoted, me);
+    assert_eq!(s.token_b.balance_of(&me) - before, quoted);
+}
+
+/// Constant-product output net of the swap fee, in basis points
+fn constant_product_out(
+    fee_rate: U256,
+    amount_in: U256,
+    reserve_in: U256,
+    reserve_out: U256,
+) -> U256 {
+    let amount_in_with_fee = amount_in * (U256::from(10_000) - fee_rate);
+    amount_in_with_fee * reserve_out / (reserve_in * U256::from(10_000) + amount_in_with_fee)
+}
+
+#[test]
+fn get_amount_out_matches_the_constant_product_formula() {
+    let mut s = seeded_pool();
+    let me = s.env.get_account(0);
+    // Skew the reserves so both directions differ
+    s.pool
+        .swap_exact_tokens_in(U256::from(20_000_000), s.token_a.address(), U256::zero(), me);
+
+    let fee_rate = s.pool.get_fee_rate();
+    let (reserve_a, reserve_b) = s.pool.get_reserves();
+    let (pool_a, pool_b) = s.pool.get_tokens();
+    for amount_in in [1u64, 1_000, 1_000_000, 50_000_000].map(U256::from) {
+        assert_eq!(
+            s.pool.get_amount_out(amount_in, pool_a),
+            constant_product_out(fee_rate, amount_in, reserve_a, reserve_b)
+        );
+        assert_eq!(
+            s.pool.get_amount_out(amount_in, pool_b),
+            constant_product_out(fee_rate, amount_in, reserve_b, reserve_a)
+        );
+    }
+
+    // A small trade is priced close to the spot rate, not a fraction of it
+    let amount_in = U256::from(1_000);
+    let spot = amount_in * reserve_b / reserve_a;
+    assert!(s.pool.get_amount_out(amount_in, pool_a) >= spot * 99 / 100);
+}
+
+#[test]
+fn swaps_pay_the_constant_product_output() {
+    let mut s = seeded_pool();
+    let other = s.env.get_account(1);
+    s.env.set_caller(other);
+    let fee_rate = s.pool.get_fee_rate();
+    let (pool_a, _) = s.pool.get_tokens();
+    let (token_a, token_b) = if pool_a == s.token_a.address() {
+        (&s.token_a, &s.token_b)
+    } else {
+        (&s.token_b, &s.token_a)
+    };
+
+    for amount_in in [1_000u64, 1_000_000, 25_000_000].map(U256::from) {
+        // token_a in
+        let (reserve_a, reserve_b) = s.pool.get_reserves();
+        let expected = constant_product_out(fee_rate, amount_in, reserve_a, reserve_b);
+        let before = token_b.balance_of(&other);
+        s.pool
+            .swap_exact_tokens_in(amount_in, token_a.address(), U256::zero(), other);
+        assert_eq!(token_b.balance_of(&other) - before, expected);
+        assert_eq!(s.pool.get_reserves(), (reserve_a + amount_in, reserve_b - expected));
+
+        // token_b in
+        let (reserve_a, reserve_b) = s.pool.get_reserves();
+        let expected = constant_product_out(fee_rate, amount_in, reserve_b, reserve_a);
+        let before = token_a.balance_of(&other);
+        s.pool
+            .swap_exact_tokens_in(amount_in, token_b.address(), U256::zero(), other);
+        assert_eq!(token_a.balance_of(&other) - before, expected);
+        assert_eq!(s.pool.get_reserves(), (reserve_a - expected, reserve_b + amount_in));
+    }
+}
+
+#[test]
+fn zap_in_deposits_both_sides() {
+    let mut s = seeded_pool();
+    let other = s.env.get_account(1);
+    s.env.set_caller(other);
+
+    let balance_a = s.token_a.balance_of(&other);
+    let balance_b = s.token_b.balance_of(&other);
+    s.pool
+        .zap_in(s.token_a.address(), U256::from(10_000_000), U256::zero(), other);
+
+    assert!(s.pool.get_lp_balance(other) > U256::zero());
+    // At most rounding dust is refunded
+    assert!(balance_a - s.token_a.balance_of(&other) > U256::from(9_999_000));
+    assert!(s.token_b.balance_of(&other) - balance_b < U256::from(1_000));
+}
+
+#[test]
+fn zap_out_pays_out_a_single_token() {
+    let mut s = seeded_pool();
+    let me = s.env.get_account(0);
+    let lp_amount = s.pool.get_lp_balance(me) / 10;
+    let supply = s.pool.get_lp_token_supply();
+    let (reserve_a, reserve_b) = s.pool.get_reserves();
+    let (pool_a, _) = s.pool.get_tokens();
+    let (token_out, token_other) = if pool_a == s.token_a.address() {
+        (&s.token_a, &s.token_b)
+    } else {
+        (&s.token_b, &s.token_a)
+    };
+
+    // Withdraw a proportional share, then swap the other side back in
+    let share_a = lp_amount * reserve_a / supply;
+    let share_b = lp_amount * reserve_b / supply;
+    let expected = share_a
+        + constant_product_out(
+            s.pool.get_fee_rate(),
+            share_b,
+            reserve_b - share_b,
+            reserve_a - share_a,
+        );
+
+    let before_out = token_out.balance_of(&me);
+    let before_other = token_other.balance_of(&me);
+    s.pool.zap_out(lp_amount, pool_a, expected);
+
+    assert_eq!(token_out.balance_of(&me) - before_out, expected);
+    assert_eq!(token_other.balance_of(&me), before_other);
+    assert_eq!(s.pool.get_lp_token_supply(), supply - lp_amount);
+    assert_eq!(s.pool.get_reserves(), (reserve_a - expected, reserve_b));
+}
+
+#[test]
+fn zap_round_trip_only_loses_fees() {
+    let mut s = seeded_pool();
+    let other = s.env.get_account(1);
+    s.env.set_caller(other);
+    let token_a = s.token_a.address();
+    let amount_in = U256::from(1_000_000);
+
+    let before = s.token_a.balance_of(&other);
+    s.pool.zap_in(token_a, amount_in, U256::zero(), other);
+    s.pool
+        .zap_out(s.pool.get_lp_balance(other), token_a, U256::zero());
+
+    assert_eq!(s.pool.get_lp_balance(other), U256::zero());
+    let lost = before - s.token_a.balance_of(&other);
+    assert!(lost > U256::zero());
+    assert!(lost < amount_in / 100);
+}
+
+#[test]
+fn zap_in_swaps_the_quoted_amount() {
+    let mut s = seeded_pool();
+    let me = s.env.get_account(0);
+    let token_a = s.token_a.address();
+    let amount_in = U256::from(10_000_000);
+    let swap_amount = s.pool.get_zap_swap_amount(amount_in, token_a);
+    let swapped_out = s.pool.get_amount_out(swap_amount, token_a);
+
+    s.pool.zap_in(token_a, amount_in, U256::zero(), me);
+    assert!(s.env.emitted_event(
+        &s.pool.address(),
+        Swap {
+            sender: me,
+            token_in: token_a,
+            token_out: s.token_b.address(),
+            amount_in: swap_amount,
+            amount_out: swapped_out,
+            to: s.pool.address(),
+        }
+    ));
+}
+
+#[test]
+fn zap_swap_amount_edge_cases() {
+    let mut s = seeded_pool();
+    let (token_a, token_b) = (s.token_a.address(), s.token_b.address());
+    let quote = |s: &common::PoolSetup, amount: u128| {
+        s.pool.get_zap_swap_amount(U256::from(amount), token_a)
+    };
+    s.pool.set_fee_rate(U256::from(30));
+
+    // Dust rounds down to nothing, or to a swap too small to pay out
+    assert_eq!(quote(&s, 1), U256::zero());
+    assert_eq!(quote(&s, 2), U256::one());
+    assert_eq!(s.pool.get_amount_out(U256::one(), token_a), U256::zero());
+
+    // Against 1e8 reserves a small deposit swaps just under half
+    assert_eq!(quote(&s, 1_000_000), U256::from(499_505));
+    assert_eq!(
+        s.pool.get_zap_swap_amount(U256::from(1_000_000), token_b),
+        U256::from(499_505)
+    );
+
+    // Far above the reserves the swap drains the other side, so only a
+    // small part of the deposit is swapped, without overflowing
+    assert_eq!(quote(&s, 100_000_000_000), U256::from(3_068_464_448u64));
+    assert_eq!(quote(&s, 10u128.pow(20)), U256::from(100_150_238_195_569u64));
+
+    // At the highest fee more of the deposit is swapped
+    s.pool.set_fee_rate(U256::from(1_000));
+    assert_eq!(quote(&s, 1), U256::zero());
+    assert_eq!(quote(&s, 1_000_000), U256::from(525_010));
+    assert_eq!(quote(&s, 100_000_000_000), U256::from(3_229_448_655u64));
+    assert_eq!(quote(&s, 10u128.pow(20)), U256::from(105_409_149_783_443u64));
+}
+
+#[test]
+fn zap_error_paths() {
+    let mut s = pool_setup();
+    let me = s.env.get_account(0);
+    let token_a = s.token_a.address();
+
+    assert_eq!(
+        s.pool.try_zap_in(token_a, U256::from(1_000), U256::zero(), me),
+        Err(Error::EmptyReserves.into())
+    );
+
+    s.pool
+        .add_liquidity(U256::from(SEED_LIQUIDITY), U256::from(SEED_LIQUIDITY));
+
+    assert_eq!(
+        s.pool.try_zap_in(token_a, U256::zero(), U256::zero(), me),
+        Err(Error::ZeroAmount.into())
+    );
+    assert_eq!(
+        s.pool.try_zap_in(s.pool.address(), U256::from(1_000), U256::zero(), me),
+        Err(Error::InvalidInputToken.into())
+    );
+    assert_eq!(
+        s.pool.try_zap_in(token_a, U256::from(1_000_000), U256::MAX, me),
+        Err(Error::LiquidityBelowMinimum.into())
+    );
+    assert_eq!(
+        s.pool.try_zap_out(U256::from(1_000), token_a, U256::MAX),
+        Err(Error::OutputBelowMinimum.into())
+    );
+    assert_eq!(
+        s.pool.try_zap_out(U256::zero(), token_a, U256::zero()),
+        Err(Error::ZeroLiquidity.into())
+    );
+}
+
+#[test]
+fn reserves_track_transfers_and_sync_picks_up_donations() {
+    let mut s = seeded_pool();
+    let me = s.env.get_account(0);
+    let amount_in = U256::from(1_000_000);
+    let amount_out = s.pool.get_amount_out(amount_in, s.token_a.address());
+    s.pool
+        .swap_exact_tokens_in(amount_in, s.token_a.address(), U256::zero(), me);
+
+    let (reserve_a, reserve_b) = s.pool.get_reserves();
+    let (pool_a, _) = s.pool.get_tokens();
+    let (reserve_in, reserve_out) = if pool_a == s.token_a.address() {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+    assert_eq!(reserve_in, U256::from(SEED_LIQUIDITY) + amount_in);
+    assert_eq!(reserve_out, U256::from(SEED_LIQUIDITY) - amount_out);
+
+    // A direct transfer isn't reflected until sync
+    s.token_a.transfer(&s.pool.address(), &U256::from(500));
+    assert_eq!(s.pool.get_reserves(), (reserve_a, reserve_b));
+
+    s.pool.sync();
+    let (synced_a, synced_b) = s.pool.get_reserves();
+    assert_eq!(synced_a + synced_b, reserve_a + reserve_b + U256::from(500));
+}